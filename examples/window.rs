@@ -1,10 +1,14 @@
+#[cfg(target_os = "macos")]
 use cocoa::appkit::NSEventModifierFlags;
+#[cfg(target_os = "macos")]
 use winit::{
   event::{Event, WindowEvent},
   event_loop::{ControlFlow, EventLoop},
   window::WindowBuilder,
 };
+#[cfg(target_os = "macos")]
 use winit_menu_macos::{
+  error::MenuError,
   event::Event::MenuEvent,
   event_channel::get_event_channel,
   menu::{set_menu, Menu},
@@ -14,7 +18,8 @@ use winit_menu_macos::{
   },
 };
 
-fn main() {
+#[cfg(target_os = "macos")]
+fn main() -> Result<(), MenuError> {
   let event_loop = EventLoop::new();
 
   let window = WindowBuilder::new()
@@ -37,20 +42,20 @@ fn main() {
       key: "a",
       masks: Some(NSEventModifierFlags::NSControlKeyMask | NSEventModifierFlags::NSCommandKeyMask),
     }),
-  );
+  )?;
   // create cumtom menu item with add_item.
   // add_item required MenuItemAttributes to setup initial state.
   // `title` is required, `selector` and `key equivalent` are optional.
   // `enabled` defult to true, `selected` defult to false.
-  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 1"));
-  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 2"));
-  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 3"));
-  app_menu.add_native_item(NativeMenuItemType::HideOthers, None, None);
-  app_menu.add_native_item(NativeMenuItemType::Separator, None, None);
-  app_menu.add_native_item(NativeMenuItemType::CloseWindow, Some("Bye"), None);
+  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 1"))?;
+  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 2"))?;
+  app_menu.add_item(MenuItemAttributes::new("AppMenu Item 3"))?;
+  app_menu.add_native_item(NativeMenuItemType::HideOthers, None, None)?;
+  app_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+  app_menu.add_native_item(NativeMenuItemType::CloseWindow, Some("Bye"), None)?;
 
   // We can't change the title of the window in macOS, so the title here is useless
-  menu_bar.add_submenu(&app_menu, "Application");
+  menu_bar.add_submenu(&app_menu, "Application")?;
 
   /* first menu */
   let first_menu: Menu = Menu::new();
@@ -64,16 +69,16 @@ fn main() {
         NSEventModifierFlags::NSAlternateKeyMask | NSEventModifierFlags::NSCommandKeyMask,
       ),
     }),
-  );
-  first_menu.add_item(MenuItemAttributes::new("Menu Item B"));
+  )?;
+  first_menu.add_item(MenuItemAttributes::new("Menu Item B"))?;
   first_menu.add_item(
     MenuItemAttributes::new("Menu Item C")
       .with_enabled(false)
       .with_selected(true),
-  );
-  first_menu.add_item(MenuItemAttributes::new("Menu Item D").with_enabled(false));
+  )?;
+  first_menu.add_item(MenuItemAttributes::new("Menu Item D").with_enabled(false))?;
 
-  menu_bar.add_submenu(&first_menu, "First Menu");
+  menu_bar.add_submenu(&first_menu, "First Menu")?;
 
   event_loop.run(move |event, _, control_flow| {
     *control_flow = ControlFlow::Wait;
@@ -86,17 +91,14 @@ fn main() {
     while let Ok(event) = rx_ref.try_recv() {
       println!("{:?}", event);
 
-      match event {
-        MenuEvent { menu_id, .. } => {
-          if menu_id == MenuId::EMPTY {
-            // menu item without title will generate a empty Id and trapped here
-            println!("EMPTY menu id");
-          } else if menu_id == enable_test_item.id() {
-            // use MenuItem.id() to get item id and match the event
-            enable_test_item.set_enabled(false);
-          }
+      if let MenuEvent { menu_id, .. } = event {
+        if menu_id == MenuId::EMPTY {
+          // menu item without title will generate a empty Id and trapped here
+          println!("EMPTY menu id");
+        } else if menu_id == enable_test_item.id() {
+          // use MenuItem.id() to get item id and match the event
+          enable_test_item.set_enabled(false);
         }
-        _ => (),
       }
     }
 
@@ -113,3 +115,8 @@ fn main() {
     }
   });
 }
+
+#[cfg(not(target_os = "macos"))]
+fn main() {
+  println!("This example only runs on macOS");
+}
@@ -0,0 +1,272 @@
+use crate::{
+  platform_impl::native_menu_item_type::NativeMenuItemType,
+  snapshot::{ItemKind, ItemSnapshot, MenuSnapshot},
+};
+use std::collections::HashSet;
+
+/// What a screen reader announces the item as, see `MenuItemAttributes::with_accessibility_role`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccessibilityRole {
+  MenuItem,
+  /// An item toggling a setting on and off.
+  CheckBox,
+  /// An item picking one option out of several.
+  RadioButton,
+  Button,
+}
+
+impl AccessibilityRole {
+  #[cfg(target_os = "macos")]
+  pub(crate) fn as_ax_role(self) -> &'static str {
+    match self {
+      AccessibilityRole::MenuItem => "AXMenuItem",
+      AccessibilityRole::CheckBox => "AXCheckBox",
+      AccessibilityRole::RadioButton => "AXRadioButton",
+      AccessibilityRole::Button => "AXButton",
+    }
+  }
+  #[cfg(target_os = "macos")]
+  pub(crate) fn from_ax_role(role: &str) -> Option<Self> {
+    match role {
+      "AXMenuItem" => Some(AccessibilityRole::MenuItem),
+      "AXCheckBox" => Some(AccessibilityRole::CheckBox),
+      "AXRadioButton" => Some(AccessibilityRole::RadioButton),
+      "AXButton" => Some(AccessibilityRole::Button),
+      _ => None,
+    }
+  }
+}
+
+/// Accessibility problem found by `audit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditIssue {
+  /// Titles of the submenus leading to the menu holding the item.
+  pub menu_path: Vec<String>,
+  /// Position of the item in its menu.
+  pub index: usize,
+  pub kind: AuditIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditIssueKind {
+  /// Neither a title, an image nor a label, screen readers have nothing to read.
+  EmptyTitle,
+  /// Only shows an image, add an accessibility label.
+  MissingLabel,
+  /// Another item of the same menu is read the same.
+  DuplicateLabel(String),
+}
+
+/// Find the items of the menu and its submenus screen reader users can't tell apart.
+///
+/// Works on a snapshot, so menus can be audited without displaying them:
+///
+/// ```ignore
+/// let issues = winit_menu_macos::accessibility::audit(&menu_bar.snapshot());
+/// assert!(issues.is_empty(), "{:?}", issues);
+/// ```
+pub fn audit(snapshot: &MenuSnapshot) -> Vec<AuditIssue> {
+  let mut issues = Vec::new();
+  audit_menu(snapshot, &mut Vec::new(), &mut issues);
+  issues
+}
+
+fn audit_menu(menu: &MenuSnapshot, path: &mut Vec<String>, issues: &mut Vec<AuditIssue>) {
+  let mut labels: HashSet<String> = HashSet::new();
+  for (index, item) in menu.items.iter().enumerate() {
+    if item.kind == ItemKind::Native(NativeMenuItemType::Separator) {
+      continue;
+    }
+    let mut issue = |kind| {
+      issues.push(AuditIssue {
+        menu_path: path.clone(),
+        index,
+        kind,
+      })
+    };
+    match spoken_label(item) {
+      Some(label) => {
+        // Only report the second and later items, screen readers don't tell cases apart
+        if !labels.insert(label.to_lowercase()) {
+          issue(AuditIssueKind::DuplicateLabel(label.to_string()));
+        }
+      }
+      None if item.has_image => issue(AuditIssueKind::MissingLabel),
+      None => issue(AuditIssueKind::EmptyTitle),
+    }
+    if let Some(submenu) = &item.submenu {
+      path.push(item.title.clone());
+      audit_menu(submenu, path, issues);
+      path.pop();
+    }
+  }
+}
+
+// What a screen reader reads, None if nothing
+fn spoken_label(item: &ItemSnapshot) -> Option<&str> {
+  item
+    .accessibility_label
+    .as_deref()
+    .or(Some(item.title.as_str()))
+    .map(str::trim)
+    .filter(|label| !label.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(title: &str) -> ItemSnapshot {
+    ItemSnapshot {
+      title: title.to_string(),
+      kind: ItemKind::Custom,
+      key_equivalent: None,
+      enabled: true,
+      selected: false,
+      submenu: None,
+      accessibility_label: None,
+      accessibility_help: None,
+      has_image: false,
+      accessibility_role: None,
+    }
+  }
+
+  fn separator() -> ItemSnapshot {
+    ItemSnapshot {
+      kind: ItemKind::Native(NativeMenuItemType::Separator),
+      ..item("")
+    }
+  }
+
+  fn icon(label: Option<&str>) -> ItemSnapshot {
+    ItemSnapshot {
+      has_image: true,
+      accessibility_label: label.map(str::to_string),
+      ..item("")
+    }
+  }
+
+  fn menu(title: &str, items: Vec<ItemSnapshot>) -> MenuSnapshot {
+    MenuSnapshot {
+      title: title.to_string(),
+      items,
+    }
+  }
+
+  fn kinds(issues: &[AuditIssue]) -> Vec<(usize, AuditIssueKind)> {
+    issues
+      .iter()
+      .map(|issue| (issue.index, issue.kind.clone()))
+      .collect()
+  }
+
+  #[test]
+  fn clean_menus_have_no_issues() {
+    let snapshot = menu(
+      "",
+      vec![
+        item("Open"),
+        separator(),
+        item("Save"),
+        separator(),
+        icon(Some("Share")),
+      ],
+    );
+    assert_eq!(audit(&snapshot), vec![]);
+  }
+
+  #[test]
+  fn missing_labels() {
+    let snapshot = menu(
+      "",
+      vec![
+        item(""),
+        item("   "),
+        icon(None),
+        icon(Some(" ")),
+        // A label replaces the title
+        ItemSnapshot {
+          accessibility_label: Some(String::new()),
+          ..item("Print")
+        },
+      ],
+    );
+    assert_eq!(
+      kinds(&audit(&snapshot)),
+      vec![
+        (0, AuditIssueKind::EmptyTitle),
+        (1, AuditIssueKind::EmptyTitle),
+        (2, AuditIssueKind::MissingLabel),
+        (3, AuditIssueKind::MissingLabel),
+        (4, AuditIssueKind::EmptyTitle),
+      ]
+    );
+  }
+
+  #[test]
+  fn duplicate_labels() {
+    let snapshot = menu(
+      "",
+      vec![
+        item("Export"),
+        item("Export "),
+        icon(Some("Export")),
+        item("Import"),
+        ItemSnapshot {
+          accessibility_label: Some("Import".to_string()),
+          ..item("Import…")
+        },
+        // Labels are compared as read
+        item("export"),
+      ],
+    );
+    assert_eq!(
+      kinds(&audit(&snapshot)),
+      vec![
+        (1, AuditIssueKind::DuplicateLabel("Export".to_string())),
+        (2, AuditIssueKind::DuplicateLabel("Export".to_string())),
+        (4, AuditIssueKind::DuplicateLabel("Import".to_string())),
+        (5, AuditIssueKind::DuplicateLabel("export".to_string())),
+      ]
+    );
+  }
+
+  #[test]
+  fn submenus_are_audited_on_their_own() {
+    let export = menu("Export", vec![item("PNG"), item("PNG"), item("Save")]);
+    let file = menu(
+      "File",
+      vec![
+        item("Save"),
+        ItemSnapshot {
+          submenu: Some(export),
+          ..item("Export")
+        },
+        icon(None),
+      ],
+    );
+    let snapshot = menu(
+      "",
+      vec![ItemSnapshot {
+        submenu: Some(file),
+        ..item("File")
+      }],
+    );
+    assert_eq!(
+      audit(&snapshot),
+      vec![
+        AuditIssue {
+          menu_path: vec!["File".to_string(), "Export".to_string()],
+          index: 1,
+          kind: AuditIssueKind::DuplicateLabel("PNG".to_string()),
+        },
+        AuditIssue {
+          menu_path: vec!["File".to_string()],
+          index: 2,
+          kind: AuditIssueKind::MissingLabel,
+        },
+      ]
+    );
+  }
+}
@@ -0,0 +1,557 @@
+use crate::platform_impl::{
+  key::{NSEventModifierFlags, OwnedKeyEquivalent, Style},
+  menu::MenuId,
+};
+#[cfg(target_os = "macos")]
+use crate::{
+  menu::Menu,
+  platform_impl::{menu::MenuTrigger, menu_item::supports_subtitles},
+};
+use std::{
+  fmt,
+  time::{Duration, Instant},
+};
+use winit::event::{ElementState, KeyboardInput, ModifiersState, VirtualKeyCode};
+
+/// One key press of a chord, such as Cmd+K.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Keystroke {
+  pub key: VirtualKeyCode,
+  pub modifiers: ModifiersState,
+}
+
+impl Keystroke {
+  pub fn new(key: VirtualKeyCode, modifiers: ModifiersState) -> Self {
+    Self { key, modifiers }
+  }
+  /// Same shortcut as an AppKit key equivalent, None for keys without one.
+  pub fn to_key_equivalent(&self) -> Option<OwnedKeyEquivalent> {
+    let mut masks = NSEventModifierFlags::empty();
+    for (modifier, mask) in [
+      (ModifiersState::CTRL, NSEventModifierFlags::NSControlKeyMask),
+      (
+        ModifiersState::ALT,
+        NSEventModifierFlags::NSAlternateKeyMask,
+      ),
+      (ModifiersState::SHIFT, NSEventModifierFlags::NSShiftKeyMask),
+      (ModifiersState::LOGO, NSEventModifierFlags::NSCommandKeyMask),
+    ] {
+      if self.modifiers.contains(modifier) {
+        masks |= mask;
+      }
+    }
+    Some(OwnedKeyEquivalent {
+      key: key_equivalent_key(self.key)?.to_string(),
+      masks,
+    })
+  }
+}
+
+impl fmt::Display for Keystroke {
+  /// Formatted like AppKit shows shortcuts, such as `⌘K`.
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.to_key_equivalent() {
+      Some(key_equivalent) => f.write_str(&key_equivalent.display(Style::MacSymbols)),
+      None => write!(f, "{:?}", self.key),
+    }
+  }
+}
+
+/// Outcome of a key press, see `ChordEngine::handle_input`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChordResult {
+  /// Not part of a chord, let the app handle the key.
+  Ignored,
+  /// Started or continued a chord, the key was consumed.
+  Pending,
+  /// Completed the chord of this item, the key was consumed.
+  Matched(MenuId),
+  /// Broke the pending chord, by pressing Escape, a key no chord continues with,
+  /// or after the timeout. The key was consumed.
+  Cancelled,
+}
+
+/// Matches multi-key shortcuts, such as Cmd+K Cmd+S, registered on menu items.
+///
+/// AppKit key equivalents only support single keystrokes, feed the keyboard events of the window
+/// to the engine instead:
+///
+/// ```ignore
+/// match event {
+///   WindowEvent::ModifiersChanged(modifiers) => chords.set_modifiers(modifiers),
+///   WindowEvent::KeyboardInput { input, .. } => {
+///     chords.handle_input_and_activate(&menu_bar, &input);
+///   }
+///   _ => (),
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChordEngine {
+  chords: Vec<(MenuId, Vec<Keystroke>)>,
+  pending: Vec<Keystroke>,
+  last_keystroke: Option<Instant>,
+  timeout: Duration,
+  modifiers: ModifiersState,
+}
+
+impl ChordEngine {
+  pub fn new() -> Self {
+    Self {
+      chords: Vec::new(),
+      pending: Vec::new(),
+      last_keystroke: None,
+      timeout: Duration::from_secs(2),
+      modifiers: ModifiersState::empty(),
+    }
+  }
+  /// Longest wait between two keystrokes of a chord. Default to 2 seconds
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+  /// Bind a chord to an item, replacing its previous chord.
+  ///
+  /// A chord is matched as soon as it is complete, so it shadows the longer chords it starts.
+  pub fn register(&mut self, menu_id: MenuId, chord: Vec<Keystroke>) {
+    self.unregister(menu_id);
+    if !chord.is_empty() {
+      self.chords.push((menu_id, chord));
+    }
+  }
+  pub fn unregister(&mut self, menu_id: MenuId) {
+    self.chords.retain(|(id, _)| *id != menu_id);
+  }
+  pub fn chord(&self, menu_id: MenuId) -> Option<&[Keystroke]> {
+    self
+      .chords
+      .iter()
+      .find(|(id, _)| *id == menu_id)
+      .map(|(_, chord)| chord.as_slice())
+  }
+  /// Keystrokes of the chord in progress, such as to show them in a status bar.
+  pub fn pending(&self) -> &[Keystroke] {
+    &self.pending
+  }
+  /// Drop the chord in progress.
+  pub fn cancel(&mut self) {
+    self.pending.clear();
+    self.last_keystroke = None;
+  }
+  /// Keep track of the modifiers, from `WindowEvent::ModifiersChanged`.
+  pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+    self.modifiers = modifiers;
+  }
+  pub fn handle_input(&mut self, input: &KeyboardInput) -> ChordResult {
+    self.handle_input_at(input, Instant::now())
+  }
+  /// Like `handle_input`, at a given time.
+  pub fn handle_input_at(&mut self, input: &KeyboardInput, now: Instant) -> ChordResult {
+    match (input.state, input.virtual_keycode) {
+      (ElementState::Pressed, Some(key)) if !is_modifier(key) => {
+        self.handle_keystroke_at(Keystroke::new(key, self.modifiers), now)
+      }
+      _ => ChordResult::Ignored,
+    }
+  }
+  pub fn handle_keystroke_at(&mut self, keystroke: Keystroke, now: Instant) -> ChordResult {
+    self.expire(now);
+    let was_pending = !self.pending.is_empty();
+    if was_pending && keystroke.key == VirtualKeyCode::Escape {
+      self.cancel();
+      return ChordResult::Cancelled;
+    }
+
+    self.pending.push(keystroke);
+    let matched = self
+      .chords
+      .iter()
+      .find(|(_, chord)| *chord == self.pending)
+      .map(|(menu_id, _)| *menu_id);
+    if let Some(menu_id) = matched {
+      self.cancel();
+      return ChordResult::Matched(menu_id);
+    }
+    let is_prefix = self
+      .chords
+      .iter()
+      .any(|(_, chord)| chord.starts_with(&self.pending));
+    if is_prefix {
+      self.last_keystroke = Some(now);
+      return ChordResult::Pending;
+    }
+
+    self.cancel();
+    match was_pending {
+      true => ChordResult::Cancelled,
+      false => ChordResult::Ignored,
+    }
+  }
+  /// Drop the chord in progress if the timeout elapsed, returns true if it was dropped.
+  ///
+  /// Keystrokes check the timeout already, call this to clear a pending indicator on time.
+  pub fn expire(&mut self, now: Instant) -> bool {
+    match self.last_keystroke {
+      Some(last) if now.saturating_duration_since(last) > self.timeout => {
+        self.cancel();
+        true
+      }
+      _ => false,
+    }
+  }
+  /// Handle the input and trigger the matched item of the menu, which sends the same menu event
+  /// as a click, reported with `MenuTrigger::Keyboard`.
+  #[cfg(target_os = "macos")]
+  pub fn handle_input_and_activate(&mut self, menu: &Menu, input: &KeyboardInput) -> ChordResult {
+    let result = self.handle_input(input);
+    if let ChordResult::Matched(menu_id) = result {
+      if let Some(item) = menu.find_by_id(menu_id) {
+        item.activate_as(MenuTrigger::Keyboard);
+      }
+    }
+    result
+  }
+  /// Show the chord of each registered item of the menu under its title, see `MenuItem::set_subtitle`.
+  ///
+  /// Subtitles require macOS 14.4. On older versions the chord is appended to the title instead,
+  /// such as `Save  ⌘K ⌘S`, so call this again after changing titles rather than before.
+  #[cfg(target_os = "macos")]
+  pub fn show_chords(&self, menu: &Menu) {
+    let subtitles = supports_subtitles();
+    for (menu_id, chord) in &self.chords {
+      if let Some(item) = menu.find_by_id(*menu_id) {
+        let chord = chord_to_string(chord);
+        if subtitles {
+          item.set_subtitle(&chord);
+        } else {
+          let suffix = format!("  {}", chord);
+          let title = item.title();
+          if !title.ends_with(&suffix) {
+            item.set_title(&(title + &suffix));
+          }
+        }
+      }
+    }
+  }
+}
+
+impl Default for ChordEngine {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Keystrokes separated by spaces, such as `⌘K ⌘S`.
+pub fn chord_to_string(chord: &[Keystroke]) -> String {
+  chord
+    .iter()
+    .map(Keystroke::to_string)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn is_modifier(key: VirtualKeyCode) -> bool {
+  matches!(
+    key,
+    VirtualKeyCode::LShift
+      | VirtualKeyCode::RShift
+      | VirtualKeyCode::LControl
+      | VirtualKeyCode::RControl
+      | VirtualKeyCode::LAlt
+      | VirtualKeyCode::RAlt
+      | VirtualKeyCode::LWin
+      | VirtualKeyCode::RWin
+  )
+}
+
+// Character AppKit uses as key equivalent for the key
+fn key_equivalent_key(key: VirtualKeyCode) -> Option<&'static str> {
+  use VirtualKeyCode::*;
+  let key = match key {
+    A => "a",
+    B => "b",
+    C => "c",
+    D => "d",
+    E => "e",
+    F => "f",
+    G => "g",
+    H => "h",
+    I => "i",
+    J => "j",
+    K => "k",
+    L => "l",
+    M => "m",
+    N => "n",
+    O => "o",
+    P => "p",
+    Q => "q",
+    R => "r",
+    S => "s",
+    T => "t",
+    U => "u",
+    V => "v",
+    W => "w",
+    X => "x",
+    Y => "y",
+    Z => "z",
+    Key0 | Numpad0 => "0",
+    Key1 | Numpad1 => "1",
+    Key2 | Numpad2 => "2",
+    Key3 | Numpad3 => "3",
+    Key4 | Numpad4 => "4",
+    Key5 | Numpad5 => "5",
+    Key6 | Numpad6 => "6",
+    Key7 | Numpad7 => "7",
+    Key8 | Numpad8 => "8",
+    Key9 | Numpad9 => "9",
+    Comma | NumpadComma => ",",
+    Period | NumpadDecimal => ".",
+    Slash | NumpadDivide => "/",
+    Semicolon => ";",
+    Apostrophe => "'",
+    LBracket => "[",
+    RBracket => "]",
+    Backslash => "\\",
+    Minus | NumpadSubtract => "-",
+    Equals | NumpadEquals => "=",
+    Plus | NumpadAdd => "+",
+    Asterisk | NumpadMultiply => "*",
+    Grave => "`",
+    Space => " ",
+    Tab => "\t",
+    Return => "\r",
+    NumpadEnter => "\u{3}",
+    Escape => "\u{1b}",
+    Back => "\u{8}",
+    Delete => "\u{f728}",
+    Up => "\u{f700}",
+    Down => "\u{f701}",
+    Left => "\u{f702}",
+    Right => "\u{f703}",
+    Home => "\u{f729}",
+    End => "\u{f72b}",
+    PageUp => "\u{f72c}",
+    PageDown => "\u{f72d}",
+    F1 => "\u{f704}",
+    F2 => "\u{f705}",
+    F3 => "\u{f706}",
+    F4 => "\u{f707}",
+    F5 => "\u{f708}",
+    F6 => "\u{f709}",
+    F7 => "\u{f70a}",
+    F8 => "\u{f70b}",
+    F9 => "\u{f70c}",
+    F10 => "\u{f70d}",
+    F11 => "\u{f70e}",
+    F12 => "\u{f70f}",
+    F13 => "\u{f710}",
+    F14 => "\u{f711}",
+    F15 => "\u{f712}",
+    F16 => "\u{f713}",
+    F17 => "\u{f714}",
+    F18 => "\u{f715}",
+    F19 => "\u{f716}",
+    F20 => "\u{f717}",
+    F21 => "\u{f718}",
+    F22 => "\u{f719}",
+    F23 => "\u{f71a}",
+    F24 => "\u{f71b}",
+    _ => return None,
+  };
+  Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn cmd(key: VirtualKeyCode) -> Keystroke {
+    Keystroke::new(key, ModifiersState::LOGO)
+  }
+
+  fn engine() -> ChordEngine {
+    let mut engine = ChordEngine::new().with_timeout(Duration::from_secs(1));
+    engine.register(
+      MenuId(1),
+      vec![cmd(VirtualKeyCode::K), cmd(VirtualKeyCode::S)],
+    );
+    engine.register(
+      MenuId(2),
+      vec![
+        cmd(VirtualKeyCode::K),
+        cmd(VirtualKeyCode::C),
+        cmd(VirtualKeyCode::C),
+      ],
+    );
+    engine
+  }
+
+  #[test]
+  fn prefixes_are_pending_until_matched() {
+    let mut engine = engine();
+    let start = Instant::now();
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), start),
+      ChordResult::Pending
+    );
+    assert_eq!(engine.pending(), &[cmd(VirtualKeyCode::K)]);
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::C), start),
+      ChordResult::Pending
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::C), start),
+      ChordResult::Matched(MenuId(2))
+    );
+    assert!(engine.pending().is_empty());
+
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), start),
+      ChordResult::Pending
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::S), start),
+      ChordResult::Matched(MenuId(1))
+    );
+    // A matched chord starts over, and keys without the modifier are other keystrokes
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::S), start),
+      ChordResult::Ignored
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(
+        Keystroke::new(VirtualKeyCode::K, ModifiersState::empty()),
+        start
+      ),
+      ChordResult::Ignored
+    );
+  }
+
+  #[test]
+  fn timeout_expires_the_pending_chord() {
+    let mut engine = engine();
+    let start = Instant::now();
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), start);
+    // Right at the timeout the chord is still pending
+    assert!(!engine.expire(start + Duration::from_secs(1)));
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::C), start + Duration::from_secs(1)),
+      ChordResult::Pending
+    );
+    // Every keystroke restarts the timeout
+    let later = start + Duration::from_millis(2500);
+    assert!(engine.expire(later));
+    assert!(engine.pending().is_empty());
+    assert!(!engine.expire(later));
+
+    // A keystroke after the timeout starts over
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), later);
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::S), later + Duration::from_secs(2)),
+      ChordResult::Ignored
+    );
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), later);
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), later + Duration::from_secs(2)),
+      ChordResult::Pending
+    );
+  }
+
+  #[test]
+  fn escape_and_other_keys_cancel() {
+    let mut engine = engine();
+    let now = Instant::now();
+    let escape = Keystroke::new(VirtualKeyCode::Escape, ModifiersState::empty());
+    // Escape is left to the app when no chord is pending
+    assert_eq!(
+      engine.handle_keystroke_at(escape, now),
+      ChordResult::Ignored
+    );
+
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now);
+    assert_eq!(
+      engine.handle_keystroke_at(escape, now),
+      ChordResult::Cancelled
+    );
+    assert!(engine.pending().is_empty());
+
+    // The key breaking the chord is consumed, it doesn't start a new chord
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now);
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::C), now);
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now),
+      ChordResult::Cancelled
+    );
+    assert!(engine.pending().is_empty());
+
+    engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now);
+    engine.cancel();
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::S), now),
+      ChordResult::Ignored
+    );
+  }
+
+  #[test]
+  fn shorter_chords_shadow_longer_ones() {
+    let mut engine = engine();
+    let now = Instant::now();
+    engine.register(MenuId(3), vec![cmd(VirtualKeyCode::K)]);
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now),
+      ChordResult::Matched(MenuId(3))
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::S), now),
+      ChordResult::Ignored
+    );
+
+    // Registering again replaces the chord of the item
+    engine.register(
+      MenuId(3),
+      vec![cmd(VirtualKeyCode::K), cmd(VirtualKeyCode::M)],
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::K), now),
+      ChordResult::Pending
+    );
+    assert_eq!(
+      engine.handle_keystroke_at(cmd(VirtualKeyCode::M), now),
+      ChordResult::Matched(MenuId(3))
+    );
+    engine.unregister(MenuId(3));
+    assert_eq!(engine.chord(MenuId(3)), None);
+    assert_eq!(engine.chord(MenuId(1)).unwrap().len(), 2);
+  }
+
+  #[test]
+  #[allow(deprecated)]
+  fn keyboard_input() {
+    let mut engine = engine();
+    let now = Instant::now();
+    let input = |state, key| KeyboardInput {
+      scancode: 0,
+      state,
+      virtual_keycode: Some(key),
+      modifiers: ModifiersState::empty(),
+    };
+    // Modifier presses and releases don't break the chord
+    engine.set_modifiers(ModifiersState::LOGO);
+    assert_eq!(
+      engine.handle_input_at(&input(ElementState::Pressed, VirtualKeyCode::LWin), now),
+      ChordResult::Ignored
+    );
+    assert_eq!(
+      engine.handle_input_at(&input(ElementState::Pressed, VirtualKeyCode::K), now),
+      ChordResult::Pending
+    );
+    assert_eq!(
+      engine.handle_input_at(&input(ElementState::Released, VirtualKeyCode::K), now),
+      ChordResult::Ignored
+    );
+    assert_eq!(
+      engine.handle_input_at(&input(ElementState::Pressed, VirtualKeyCode::S), now),
+      ChordResult::Matched(MenuId(1))
+    );
+    assert_eq!(chord_to_string(engine.chord(MenuId(1)).unwrap()), "⌘K ⌘S");
+  }
+}
@@ -0,0 +1,71 @@
+use std::{error::Error, fmt};
+
+/// Error returned when a menu or menu item can't be created.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuError {
+  /// AppKit objects can only be created and updated on the main thread.
+  NotMainThread,
+  /// The Objective-C class backing custom menu items can't be registered,
+  /// incompatible classes already use its name and every fallback name.
+  ClassRegistration(String),
+  /// AppKit returned nil when creating the object.
+  AllocationFailed,
+  /// The bounds or value of a `MenuItemView::Slider` are not finite, or the value is out of
+  /// the bounds.
+  InvalidSlider,
+}
+
+impl fmt::Display for MenuError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MenuError::NotMainThread => write!(f, "menus can only be used on the main thread"),
+      MenuError::ClassRegistration(name) => {
+        write!(f, "failed to register the Objective-C class `{}`", name)
+      }
+      MenuError::AllocationFailed => write!(f, "AppKit failed to allocate the menu item"),
+      MenuError::InvalidSlider => write!(f, "the slider value must be within finite bounds"),
+    }
+  }
+}
+
+impl Error for MenuError {}
+
+/// Error returned when a global shortcut can't be registered.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalShortcutError {
+  /// Global shortcuts can only be registered and unregistered on the main thread.
+  NotMainThread,
+  /// The key has no virtual key code, such as a character outside of the US layout.
+  UnsupportedKey(String),
+  /// The shortcut has none of command, control and option.
+  MissingModifier,
+  /// The system refused the shortcut, usually because another app registered it.
+  Registration(i32),
+}
+
+impl fmt::Display for GlobalShortcutError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GlobalShortcutError::NotMainThread => {
+        write!(f, "global shortcuts can only be changed on the main thread")
+      }
+      GlobalShortcutError::UnsupportedKey(key) => {
+        write!(f, "`{}` can't be used in a global shortcut", key)
+      }
+      GlobalShortcutError::MissingModifier => {
+        write!(f, "global shortcuts need command, control or option")
+      }
+      GlobalShortcutError::Registration(status) => {
+        write!(
+          f,
+          "failed to register the global shortcut (status {})",
+          status
+        )
+      }
+    }
+  }
+}
+
+impl Error for GlobalShortcutError {}
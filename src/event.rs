@@ -1,11 +1,23 @@
-use crate::platform_impl::menu::{Id, MenuId, MenuType};
+use crate::platform_impl::{
+  key::NSEventModifierFlags,
+  menu::{Id, MenuId, MenuTrigger, MenuType},
+};
 
 #[non_exhaustive]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
   MenuEvent {
     window_id: Option<Id>,
     menu_id: MenuId,
     menu_type: MenuType,
+    /// Modifier keys held when the item was triggered.
+    modifiers: NSEventModifierFlags,
+    trigger: MenuTrigger,
+    /// Whether the item was checked when triggered.
+    checked: bool,
+    /// Titles of the submenus leading to the item, from the menu bar down.
+    path: Vec<String>,
   },
+  /// The value of a view item changed, such as a slider being dragged. See `MenuItemView`.
+  ViewValueChanged { menu_id: MenuId, value: f64 },
 }
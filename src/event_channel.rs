@@ -1,9 +1,374 @@
 use crate::event::Event;
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender, TryIter, TrySendError};
 use once_cell::sync::OnceCell;
+use std::{
+  ops::Deref,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex, PoisonError,
+  },
+};
+
+pub(crate) type Notify = Arc<dyn Fn() + Send + Sync>;
 
 /// Event channel for receiving events from the menu click.
+///
+/// The channel is created by the first call, events dispatched before are not kept,
+/// so call it while setting up the menu. Every component draining this channel steals events
+/// from the others, use `subscribe` to get a receiver of your own.
 pub fn get_event_channel() -> &'static (Sender<Event>, Receiver<Event>) {
+  get_shared_channel().get_or_init(unbounded::<Event>)
+}
+
+fn get_shared_channel() -> &'static OnceCell<(Sender<Event>, Receiver<Event>)> {
   static CHANNEL: OnceCell<(Sender<Event>, Receiver<Event>)> = OnceCell::new();
-  CHANNEL.get_or_init(|| unbounded::<Event>())
+  &CHANNEL
+}
+
+/// Iterate over the events pending in the shared event channel, without blocking.
+pub fn try_iter() -> TryIter<'static, Event> {
+  get_event_channel().1.try_iter()
+}
+
+/// What to do with a new event when a bounded subscription is full.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OverflowPolicy {
+  /// Drop the oldest pending event to make room for the new one.
+  DropOldest,
+  /// Drop the new event.
+  DropNewest,
+  /// Wait until the subscriber receives an event.
+  ///
+  /// Events are sent from the main thread, so a subscriber that stops receiving freezes the app.
+  /// Never receive such a subscription on the main thread itself: the first event that doesn't
+  /// fit deadlocks it.
+  Block,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscribeOptions {
+  /// Maximum number of pending events, at least 1. None for unbounded. Default to None
+  pub capacity: Option<usize>,
+  /// Only used with a capacity. Default to `OverflowPolicy::DropOldest`
+  pub overflow: OverflowPolicy,
+}
+
+impl SubscribeOptions {
+  pub fn new() -> Self {
+    Self {
+      capacity: None,
+      overflow: OverflowPolicy::DropOldest,
+    }
+  }
+  pub fn with_capacity(mut self, capacity: usize) -> Self {
+    self.capacity = Some(capacity);
+    self
+  }
+  pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+    self.overflow = overflow;
+    self
+  }
+}
+
+impl Default for SubscribeOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[derive(Clone)]
+struct Subscriber {
+  id: usize,
+  tx: Sender<Event>,
+  // Kept to drop the oldest event on overflow
+  rx: Option<Receiver<Event>>,
+  overflow: OverflowPolicy,
+  // Called after each event is queued
+  notify: Option<Notify>,
+}
+
+impl Subscriber {
+  // Returns false once the subscriber is gone
+  fn send(&self, event: Event) -> bool {
+    let connected = self.queue(event);
+    if let Some(notify) = &self.notify {
+      notify();
+    }
+    connected
+  }
+  fn queue(&self, event: Event) -> bool {
+    match self.overflow {
+      OverflowPolicy::Block => self.tx.send(event).is_ok(),
+      OverflowPolicy::DropNewest => {
+        !matches!(self.tx.try_send(event), Err(TrySendError::Disconnected(_)))
+      }
+      OverflowPolicy::DropOldest => {
+        let mut event = event;
+        loop {
+          match self.tx.try_send(event) {
+            Ok(()) => return true,
+            Err(TrySendError::Disconnected(_)) => return false,
+            Err(TrySendError::Full(rejected)) => {
+              if let Some(rx) = &self.rx {
+                let _ = rx.try_recv();
+              }
+              event = rejected;
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+fn get_subscribers() -> &'static Mutex<Vec<Subscriber>> {
+  static SUBSCRIBERS: OnceCell<Mutex<Vec<Subscriber>>> = OnceCell::new();
+  SUBSCRIBERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Get a receiver of your own, receiving every menu event from now on.
+/// Unsubscribed once the receiver and its clones are dropped.
+pub fn subscribe() -> Receiver<Event> {
+  subscribe_with_notify(SubscribeOptions::new(), None)
+}
+
+/// Like `subscribe`, with a bounded capacity and overflow policy.
+///
+/// Receivers with `OverflowPolicy::DropOldest` can't be detected as dropped, so the subscription
+/// ends when the returned `Subscription` is dropped, even if clones of its receiver are alive.
+pub fn subscribe_with(options: SubscribeOptions) -> Subscription {
+  let (receiver, id) = add_subscriber(options, None);
+  Subscription { receiver, id }
+}
+
+pub(crate) fn subscribe_with_notify(
+  options: SubscribeOptions,
+  notify: Option<Notify>,
+) -> Receiver<Event> {
+  add_subscriber(options, notify).0
+}
+
+fn add_subscriber(options: SubscribeOptions, notify: Option<Notify>) -> (Receiver<Event>, usize) {
+  static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+  let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+  let (tx, rx) = match options.capacity {
+    Some(capacity) => bounded::<Event>(capacity.max(1)),
+    None => unbounded::<Event>(),
+  };
+  let keep_receiver = options.capacity.is_some() && options.overflow == OverflowPolicy::DropOldest;
+  let subscriber = Subscriber {
+    id,
+    tx,
+    rx: match keep_receiver {
+      true => Some(rx.clone()),
+      false => None,
+    },
+    overflow: options.overflow,
+    notify,
+  };
+  get_subscribers()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .push(subscriber);
+  (rx, id)
+}
+
+/// Receiver returned by `subscribe_with`, unsubscribed when dropped.
+#[derive(Debug)]
+pub struct Subscription {
+  receiver: Receiver<Event>,
+  id: usize,
+}
+
+impl Subscription {
+  pub fn receiver(&self) -> &Receiver<Event> {
+    &self.receiver
+  }
+}
+
+impl Deref for Subscription {
+  type Target = Receiver<Event>;
+
+  fn deref(&self) -> &Receiver<Event> {
+    &self.receiver
+  }
+}
+
+impl Drop for Subscription {
+  fn drop(&mut self) {
+    get_subscribers()
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .retain(|subscriber| subscriber.id != self.id);
+  }
+}
+
+// Used by subscribers without a receiver to identify them, such as `MenuEventStream`
+#[cfg(feature = "async")]
+pub(crate) fn unsubscribe_notify(notify: &Notify) {
+  get_subscribers()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .retain(|subscriber| !has_notify(subscriber, notify));
+}
+
+#[cfg(test)]
+pub(crate) fn is_subscribed(notify: &Notify) -> bool {
+  get_subscribers()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .iter()
+    .any(|subscriber| has_notify(subscriber, notify))
+}
+
+#[cfg(any(test, feature = "async"))]
+fn has_notify(subscriber: &Subscriber, notify: &Notify) -> bool {
+  matches!(&subscriber.notify, Some(other) if Arc::ptr_eq(other, notify))
+}
+
+/// Deliver an event to the shared channel and every subscriber. Never panics.
+pub(crate) fn dispatch_event(event: Event) {
+  // Only fill the shared channel if someone asked for it, nobody would drain it otherwise
+  if let Some((tx, _)) = get_shared_channel().get() {
+    let _ = tx.send(event.clone());
+  }
+  // Send outside of the lock, a blocking subscriber would hold up subscribing and unsubscribing
+  let subscribers = get_subscribers()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .clone();
+  let disconnected: Vec<Sender<Event>> = subscribers
+    .into_iter()
+    .filter(|subscriber| !subscriber.send(event.clone()))
+    .map(|subscriber| subscriber.tx)
+    .collect();
+  if !disconnected.is_empty() {
+    get_subscribers()
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .retain(|subscriber| {
+        !disconnected
+          .iter()
+          .any(|tx| tx.same_channel(&subscriber.tx))
+      });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform_impl::menu::MenuId;
+  use std::{thread, time::Duration};
+
+  // Subscribers are global and tests run in parallel, so each test uses its own ids
+  fn event(id: u16) -> Event {
+    Event::ViewValueChanged {
+      menu_id: MenuId(id),
+      value: 0.,
+    }
+  }
+
+  fn received(rx: &Receiver<Event>, ids: std::ops::Range<u16>) -> Vec<u16> {
+    rx.try_iter()
+      .filter_map(|event| match event {
+        Event::ViewValueChanged { menu_id, .. } if ids.contains(&menu_id.0) => Some(menu_id.0),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn every_subscriber_receives_events() {
+    let first = subscribe();
+    let second = subscribe();
+    dispatch_event(event(100));
+    dispatch_event(event(101));
+    assert_eq!(received(&first, 100..200), vec![100, 101]);
+    assert_eq!(received(&second, 100..200), vec![100, 101]);
+  }
+
+  #[test]
+  fn overflow_policies() {
+    let oldest = subscribe_with(SubscribeOptions::new().with_capacity(2));
+    let newest = subscribe_with(
+      SubscribeOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropNewest),
+    );
+    for id in 200..204 {
+      dispatch_event(event(id));
+    }
+    // Events of other tests may take room in the bounded channels, only check the order
+    let kept = received(&oldest, 200..300);
+    assert!(kept.windows(2).all(|ids| ids[0] < ids[1]));
+    assert_eq!(kept.last(), Some(&203));
+    assert!(!received(&newest, 200..300).contains(&203));
+  }
+
+  #[test]
+  fn dropped_subscriptions_are_removed() {
+    let oldest = subscribe_with(SubscribeOptions::new().with_capacity(1));
+    let id = oldest.id;
+    let is_subscribed = || {
+      get_subscribers()
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|subscriber| subscriber.id == id)
+    };
+    dispatch_event(event(250));
+    assert!(is_subscribed());
+    drop(oldest);
+    assert!(!is_subscribed());
+  }
+
+  #[test]
+  fn dropped_subscribers_are_removed() {
+    // Tell the subscriber apart from the ones of other tests by its notify function
+    let notify: Notify = Arc::new(|| ());
+    let rx = subscribe_with_notify(SubscribeOptions::new(), Some(notify.clone()));
+    assert!(is_subscribed(&notify));
+    drop(rx);
+    dispatch_event(event(300));
+    assert!(!is_subscribed(&notify));
+  }
+
+  #[test]
+  fn blocked_subscriber_doesnt_hold_the_lock() {
+    let blocked = subscribe_with(
+      SubscribeOptions::new()
+        .with_capacity(1)
+        .with_overflow(OverflowPolicy::Block),
+    );
+    let dispatcher = thread::spawn(|| {
+      for id in 400..403 {
+        dispatch_event(event(id));
+      }
+    });
+    thread::sleep(Duration::from_millis(50));
+    // Would deadlock if the dispatcher was blocked while holding the subscribers
+    let other = subscribe_with(SubscribeOptions::new());
+    drop(other);
+    let mut ids = Vec::new();
+    while ids.len() < 3 {
+      if let Ok(Event::ViewValueChanged { menu_id, .. }) =
+        blocked.recv_timeout(Duration::from_secs(5))
+      {
+        if (400..403).contains(&menu_id.0) {
+          ids.push(menu_id.0);
+        }
+      }
+    }
+    dispatcher.join().unwrap();
+    assert_eq!(ids, vec![400, 401, 402]);
+  }
+
+  #[test]
+  fn shared_channel_only_keeps_events_once_created() {
+    // No other test creates the shared channel
+    dispatch_event(event(500));
+    let (_, rx) = get_event_channel();
+    dispatch_event(event(501));
+    assert_eq!(received(rx, 500..600), vec![501]);
+  }
 }
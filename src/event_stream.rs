@@ -0,0 +1,121 @@
+use crate::{
+  event::Event,
+  event_channel::{subscribe_with_notify, unsubscribe_notify, Notify, SubscribeOptions},
+};
+use crossbeam_channel::{Receiver, TryRecvError};
+use futures_core::Stream;
+use std::{
+  fmt,
+  pin::Pin,
+  sync::{Arc, Mutex, PoisonError},
+  task::{Context, Poll, Waker},
+};
+
+/// Stream of menu events, see `menu_events`. Unsubscribed when dropped.
+pub struct MenuEventStream {
+  rx: Receiver<Event>,
+  waker: Arc<Mutex<Option<Waker>>>,
+  // Identifies the subscription, the receiver may be kept by the subscriber
+  notify: Notify,
+}
+
+/// Get a stream receiving every menu event from now on, independently of other subscribers.
+///
+/// ```ignore
+/// let mut events = winit_menu_macos::event_stream::menu_events();
+/// while let Some(event) = events.next().await {
+///   println!("{:?}", event);
+/// }
+/// ```
+pub fn menu_events() -> MenuEventStream {
+  menu_events_with(SubscribeOptions::new())
+}
+
+/// Like `menu_events`, with a bounded capacity and overflow policy.
+pub fn menu_events_with(options: SubscribeOptions) -> MenuEventStream {
+  let waker: Arc<Mutex<Option<Waker>>> = Arc::new(Mutex::new(None));
+  let notify_waker = waker.clone();
+  let notify: Notify = Arc::new(move || {
+    let waker = notify_waker
+      .lock()
+      .unwrap_or_else(PoisonError::into_inner)
+      .take();
+    if let Some(waker) = waker {
+      waker.wake();
+    }
+  });
+  let rx = subscribe_with_notify(options, Some(notify.clone()));
+  MenuEventStream { rx, waker, notify }
+}
+
+impl Drop for MenuEventStream {
+  fn drop(&mut self) {
+    unsubscribe_notify(&self.notify);
+  }
+}
+
+impl fmt::Debug for MenuEventStream {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MenuEventStream")
+      .field("rx", &self.rx)
+      .field("waker", &self.waker)
+      .finish()
+  }
+}
+
+impl Stream for MenuEventStream {
+  type Item = Event;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+    match self.rx.try_recv() {
+      Ok(event) => return Poll::Ready(Some(event)),
+      Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+      Err(TryRecvError::Empty) => (),
+    }
+
+    *self.waker.lock().unwrap_or_else(PoisonError::into_inner) = Some(cx.waker().clone());
+
+    // An event may have been sent before the waker was stored
+    match self.rx.try_recv() {
+      Ok(event) => Poll::Ready(Some(event)),
+      Err(TryRecvError::Disconnected) => Poll::Ready(None),
+      Err(TryRecvError::Empty) => Poll::Pending,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    event_channel::{dispatch_event, is_subscribed, OverflowPolicy},
+    platform_impl::menu::MenuId,
+  };
+
+  #[test]
+  fn dropped_stream_unsubscribes() {
+    // Kept receivers never disconnect, only dropping the stream unsubscribes them
+    for overflow in [
+      OverflowPolicy::DropOldest,
+      OverflowPolicy::DropNewest,
+      OverflowPolicy::Block,
+    ] {
+      let stream = menu_events_with(
+        SubscribeOptions::new()
+          .with_capacity(1)
+          .with_overflow(overflow),
+      );
+      let notify = stream.notify.clone();
+      assert!(is_subscribed(&notify));
+      drop(stream);
+      assert!(!is_subscribed(&notify));
+      // Would block forever with a full `Block` subscription left behind
+      for value in 0..3 {
+        dispatch_event(Event::ViewValueChanged {
+          menu_id: MenuId(600),
+          value: value as f64,
+        });
+      }
+    }
+  }
+}
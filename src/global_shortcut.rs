@@ -0,0 +1,565 @@
+use crate::{
+  error::GlobalShortcutError,
+  event::Event,
+  event_channel::dispatch_event,
+  platform_impl::{
+    key::{KeyEquivalent, NSEventModifierFlags, OwnedKeyEquivalent},
+    menu::{MenuId, MenuTrigger, MenuType},
+  },
+};
+use std::{cell::RefCell, collections::HashMap};
+
+#[cfg(target_os = "macos")]
+use carbon as backend;
+#[cfg(not(target_os = "macos"))]
+use recording as backend;
+
+#[cfg(not(target_os = "macos"))]
+pub use recording::recorded_registrations;
+
+struct Registration {
+  key_equivalent: OwnedKeyEquivalent,
+  hot_key: backend::HotKey,
+}
+
+thread_local! {
+  // Registered shortcuts, only ever touched on the main thread.
+  static REGISTRATIONS: RefCell<HashMap<MenuId, Registration>> = RefCell::new(HashMap::new());
+}
+
+/// Trigger the item with this id whenever the shortcut is pressed, even while the app is not
+/// frontmost. Replaces the previous global shortcut of the item, which is kept if the new one
+/// can't be registered.
+///
+/// The item sends the same menu event as a click, reported with `MenuTrigger::Keyboard`.
+/// Keys are matched by their position on a US keyboard, whatever the current layout.
+/// The shortcut needs command, control or option, so it doesn't take over typing in other apps.
+pub fn register(menu_id: MenuId, key_equivalent: KeyEquivalent) -> Result<(), GlobalShortcutError> {
+  if !backend::is_main_thread() {
+    return Err(GlobalShortcutError::NotMainThread);
+  }
+  let key_equivalent = key_equivalent.into_owned();
+  let (key_code, modifiers) = hot_key_of(&key_equivalent)?;
+  let current = REGISTRATIONS.with(|registrations| {
+    registrations
+      .borrow()
+      .get(&menu_id)
+      .map(|registration| hot_key_of(&registration.key_equivalent))
+  });
+  // The system refuses to register the same hot key twice
+  if let Some(Ok(current)) = current {
+    if current == (key_code, modifiers) {
+      return Ok(());
+    }
+  }
+  let hot_key = backend::register_hot_key(menu_id.0 as u32, key_code, modifiers)
+    .map_err(GlobalShortcutError::Registration)?;
+  let previous = REGISTRATIONS.with(|registrations| {
+    registrations.borrow_mut().insert(
+      menu_id,
+      Registration {
+        key_equivalent,
+        hot_key,
+      },
+    )
+  });
+  if let Some(previous) = previous {
+    backend::unregister_hot_key(previous.hot_key);
+  }
+  Ok(())
+}
+
+/// Remove the global shortcut of an item, returns false if it had none.
+pub fn unregister(menu_id: MenuId) -> Result<bool, GlobalShortcutError> {
+  if !backend::is_main_thread() {
+    return Err(GlobalShortcutError::NotMainThread);
+  }
+  let registration =
+    REGISTRATIONS.with(|registrations| registrations.borrow_mut().remove(&menu_id));
+  Ok(match registration {
+    Some(registration) => {
+      backend::unregister_hot_key(registration.hot_key);
+      true
+    }
+    None => false,
+  })
+}
+
+/// Global shortcuts registered on this thread, sorted by id.
+pub fn registered() -> Vec<(MenuId, OwnedKeyEquivalent)> {
+  let mut registered: Vec<(MenuId, OwnedKeyEquivalent)> = REGISTRATIONS.with(|registrations| {
+    registrations
+      .borrow()
+      .iter()
+      .map(|(menu_id, registration)| (*menu_id, registration.key_equivalent.clone()))
+      .collect()
+  });
+  registered.sort_by_key(|(menu_id, _)| *menu_id);
+  registered
+}
+
+/// Act as if the global shortcut of the item was pressed. Must be called on the main thread.
+pub fn simulate_press(menu_id: MenuId) {
+  on_hot_key_pressed(menu_id);
+}
+
+fn on_hot_key_pressed(menu_id: MenuId) {
+  let masks = REGISTRATIONS.with(|registrations| {
+    registrations
+      .borrow()
+      .get(&menu_id)
+      .map(|registration| registration.key_equivalent.masks)
+  });
+  let masks = match masks {
+    Some(masks) => masks,
+    None => return,
+  };
+  // Go through the item when it is in the menu bar, so the event is the same as a click
+  if backend::activate_main_menu_item(menu_id) {
+    return;
+  }
+  dispatch_event(Event::MenuEvent {
+    window_id: None,
+    menu_id,
+    menu_type: MenuType::MenuBar,
+    modifiers: masks,
+    trigger: MenuTrigger::Keyboard,
+    checked: false,
+    path: Vec::new(),
+  });
+}
+
+// Carbon modifier flags, see Events.h
+const CMD_KEY: u32 = 1 << 8;
+const SHIFT_KEY: u32 = 1 << 9;
+const OPTION_KEY: u32 = 1 << 11;
+const CONTROL_KEY: u32 = 1 << 12;
+
+fn hot_key_of(key_equivalent: &OwnedKeyEquivalent) -> Result<(u32, u32), GlobalShortcutError> {
+  let unsupported = || GlobalShortcutError::UnsupportedKey(key_equivalent.key.clone());
+  let mut chars = key_equivalent.key.chars();
+  let key = match (chars.next(), chars.next()) {
+    (Some(key), None) => key,
+    _ => return Err(unsupported()),
+  };
+  let key_code = virtual_key_code(key.to_ascii_lowercase()).ok_or_else(unsupported)?;
+
+  let masks = key_equivalent.masks;
+  let mut modifiers = 0;
+  for (mask, modifier) in [
+    (NSEventModifierFlags::NSCommandKeyMask, CMD_KEY),
+    (NSEventModifierFlags::NSShiftKeyMask, SHIFT_KEY),
+    (NSEventModifierFlags::NSAlternateKeyMask, OPTION_KEY),
+    (NSEventModifierFlags::NSControlKeyMask, CONTROL_KEY),
+  ] {
+    if masks.contains(mask) {
+      modifiers |= modifier;
+    }
+  }
+  if modifiers & (CMD_KEY | OPTION_KEY | CONTROL_KEY) == 0 {
+    return Err(GlobalShortcutError::MissingModifier);
+  }
+  // Like key equivalents, an uppercase letter implies shift
+  if key.is_uppercase() {
+    modifiers |= SHIFT_KEY;
+  }
+  Ok((key_code, modifiers))
+}
+
+// Virtual key code of the key on a US keyboard, see kVK_* in Events.h
+fn virtual_key_code(key: char) -> Option<u32> {
+  let key_code = match key {
+    'a' => 0x00,
+    's' => 0x01,
+    'd' => 0x02,
+    'f' => 0x03,
+    'h' => 0x04,
+    'g' => 0x05,
+    'z' => 0x06,
+    'x' => 0x07,
+    'c' => 0x08,
+    'v' => 0x09,
+    'b' => 0x0B,
+    'q' => 0x0C,
+    'w' => 0x0D,
+    'e' => 0x0E,
+    'r' => 0x0F,
+    'y' => 0x10,
+    't' => 0x11,
+    '1' => 0x12,
+    '2' => 0x13,
+    '3' => 0x14,
+    '4' => 0x15,
+    '6' => 0x16,
+    '5' => 0x17,
+    '=' => 0x18,
+    '9' => 0x19,
+    '7' => 0x1A,
+    '-' => 0x1B,
+    '8' => 0x1C,
+    '0' => 0x1D,
+    ']' => 0x1E,
+    'o' => 0x1F,
+    'u' => 0x20,
+    '[' => 0x21,
+    'i' => 0x22,
+    'p' => 0x23,
+    '\r' => 0x24,
+    'l' => 0x25,
+    'j' => 0x26,
+    '\'' => 0x27,
+    'k' => 0x28,
+    ';' => 0x29,
+    '\\' => 0x2A,
+    ',' => 0x2B,
+    '/' => 0x2C,
+    'n' => 0x2D,
+    'm' => 0x2E,
+    '.' => 0x2F,
+    '\t' => 0x30,
+    ' ' => 0x31,
+    '`' => 0x32,
+    '\u{8}' | '\u{7f}' => 0x33,
+    '\u{1b}' => 0x35,
+    '\u{f704}' => 0x7A,
+    '\u{f705}' => 0x78,
+    '\u{f706}' => 0x63,
+    '\u{f707}' => 0x76,
+    '\u{f708}' => 0x60,
+    '\u{f709}' => 0x61,
+    '\u{f70a}' => 0x62,
+    '\u{f70b}' => 0x64,
+    '\u{f70c}' => 0x65,
+    '\u{f70d}' => 0x6D,
+    '\u{f70e}' => 0x67,
+    '\u{f70f}' => 0x6F,
+    '\u{f729}' => 0x73,
+    '\u{f72c}' => 0x74,
+    '\u{f728}' => 0x75,
+    '\u{f72b}' => 0x77,
+    '\u{f72d}' => 0x79,
+    '\u{f702}' => 0x7B,
+    '\u{f703}' => 0x7C,
+    '\u{f701}' => 0x7D,
+    '\u{f700}' => 0x7E,
+    _ => return None,
+  };
+  Some(key_code)
+}
+
+#[cfg(target_os = "macos")]
+mod carbon {
+  use super::on_hot_key_pressed;
+  pub use crate::platform_impl::menu::is_main_thread;
+  use crate::platform_impl::{
+    menu::{MenuId, MenuTrigger},
+    menu_item::{activate_menu_item_as, find_menu_item},
+  };
+  use cocoa::base::{id, nil};
+  use objc::{class, msg_send, sel, sel_impl};
+  use once_cell::sync::OnceCell;
+  use std::{ffi::c_void, mem, ptr};
+
+  type OSStatus = i32;
+  type EventRef = *mut c_void;
+  type EventTargetRef = *mut c_void;
+  type EventHotKeyRef = *mut c_void;
+  type EventHandlerCallRef = *mut c_void;
+  type EventHandlerRef = *mut c_void;
+  type EventHandlerProcPtr = extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+  #[repr(C)]
+  #[derive(Default)]
+  struct EventHotKeyID {
+    signature: u32,
+    id: u32,
+  }
+
+  #[repr(C)]
+  struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+  }
+
+  const NO_ERR: OSStatus = 0;
+  const EVENT_NOT_HANDLED_ERR: OSStatus = -9874;
+  // Four char codes, see CarbonEvents.h
+  const K_EVENT_CLASS_KEYBOARD: u32 = u32::from_be_bytes(*b"keyb");
+  const K_EVENT_HOT_KEY_PRESSED: u32 = 5;
+  const K_EVENT_PARAM_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+  const TYPE_EVENT_HOT_KEY_ID: u32 = u32::from_be_bytes(*b"hkid");
+  // Tells our hot keys apart from the ones of other libraries in the process
+  const SIGNATURE: u32 = u32::from_be_bytes(*b"wmnu");
+
+  #[link(name = "Carbon", kind = "framework")]
+  extern "C" {
+    fn GetApplicationEventTarget() -> EventTargetRef;
+    fn InstallEventHandler(
+      target: EventTargetRef,
+      handler: EventHandlerProcPtr,
+      num_types: usize,
+      list: *const EventTypeSpec,
+      user_data: *mut c_void,
+      out_ref: *mut EventHandlerRef,
+    ) -> OSStatus;
+    fn RegisterEventHotKey(
+      hot_key_code: u32,
+      hot_key_modifiers: u32,
+      hot_key_id: EventHotKeyID,
+      target: EventTargetRef,
+      options: u32,
+      out_ref: *mut EventHotKeyRef,
+    ) -> OSStatus;
+    fn UnregisterEventHotKey(hot_key: EventHotKeyRef) -> OSStatus;
+    fn GetEventParameter(
+      event: EventRef,
+      name: u32,
+      desired_type: u32,
+      actual_type: *mut u32,
+      buffer_size: usize,
+      actual_size: *mut usize,
+      data: *mut c_void,
+    ) -> OSStatus;
+  }
+
+  pub struct HotKey(EventHotKeyRef);
+
+  extern "C" fn hot_key_handler(
+    _: EventHandlerCallRef,
+    event: EventRef,
+    _: *mut c_void,
+  ) -> OSStatus {
+    let mut hot_key_id = EventHotKeyID::default();
+    let status = unsafe {
+      GetEventParameter(
+        event,
+        K_EVENT_PARAM_DIRECT_OBJECT,
+        TYPE_EVENT_HOT_KEY_ID,
+        ptr::null_mut(),
+        mem::size_of::<EventHotKeyID>(),
+        ptr::null_mut(),
+        &mut hot_key_id as *mut EventHotKeyID as *mut c_void,
+      )
+    };
+    if status != NO_ERR || hot_key_id.signature != SIGNATURE {
+      return EVENT_NOT_HANDLED_ERR;
+    }
+    // Never unwind into Carbon
+    let _ = std::panic::catch_unwind(|| on_hot_key_pressed(MenuId(hot_key_id.id as u16)));
+    NO_ERR
+  }
+
+  fn install_handler() -> Result<(), OSStatus> {
+    static INSTALLED: OnceCell<Result<(), OSStatus>> = OnceCell::new();
+    *INSTALLED.get_or_init(|| unsafe {
+      let event_type = EventTypeSpec {
+        event_class: K_EVENT_CLASS_KEYBOARD,
+        event_kind: K_EVENT_HOT_KEY_PRESSED,
+      };
+      let status = InstallEventHandler(
+        GetApplicationEventTarget(),
+        hot_key_handler,
+        1,
+        &event_type,
+        ptr::null_mut(),
+        ptr::null_mut(),
+      );
+      match status {
+        NO_ERR => Ok(()),
+        status => Err(status),
+      }
+    })
+  }
+
+  pub fn register_hot_key(id: u32, key_code: u32, modifiers: u32) -> Result<HotKey, OSStatus> {
+    install_handler()?;
+    let mut hot_key: EventHotKeyRef = ptr::null_mut();
+    let status = unsafe {
+      RegisterEventHotKey(
+        key_code,
+        modifiers,
+        EventHotKeyID {
+          signature: SIGNATURE,
+          id,
+        },
+        GetApplicationEventTarget(),
+        0,
+        &mut hot_key,
+      )
+    };
+    match status {
+      NO_ERR => Ok(HotKey(hot_key)),
+      status => Err(status),
+    }
+  }
+
+  pub fn unregister_hot_key(hot_key: HotKey) {
+    unsafe {
+      UnregisterEventHotKey(hot_key.0);
+    }
+  }
+
+  pub fn activate_main_menu_item(menu_id: MenuId) -> bool {
+    unsafe {
+      let app: id = msg_send![class!(NSApplication), sharedApplication];
+      let main_menu: id = msg_send![app, mainMenu];
+      if main_menu == nil {
+        return false;
+      }
+      match find_menu_item(main_menu, menu_id) {
+        Some(item) => {
+          activate_menu_item_as(item, MenuTrigger::Keyboard);
+          true
+        }
+        None => false,
+      }
+    }
+  }
+}
+
+// Keeps the registrations instead of handing them to the system, so they can be tested without
+// AppKit.
+#[cfg(not(target_os = "macos"))]
+mod recording {
+  use crate::platform_impl::menu::MenuId;
+  use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+  };
+
+  thread_local! {
+    // Hot keys by token, as id, virtual key code and Carbon modifiers
+    static REGISTERED: RefCell<BTreeMap<usize, (u32, u32, u32)>> =
+      const { RefCell::new(BTreeMap::new()) };
+    static NEXT_TOKEN: Cell<usize> = const { Cell::new(0) };
+  }
+
+  pub struct HotKey(usize);
+
+  // There is no main thread to stay on, registrations are per thread
+  pub fn is_main_thread() -> bool {
+    true
+  }
+
+  pub fn register_hot_key(id: u32, key_code: u32, modifiers: u32) -> Result<HotKey, i32> {
+    let token = NEXT_TOKEN.with(|next| {
+      next.set(next.get() + 1);
+      next.get()
+    });
+    REGISTERED.with(|registered| {
+      registered
+        .borrow_mut()
+        .insert(token, (id, key_code, modifiers))
+    });
+    Ok(HotKey(token))
+  }
+
+  pub fn unregister_hot_key(hot_key: HotKey) {
+    REGISTERED.with(|registered| registered.borrow_mut().remove(&hot_key.0));
+  }
+
+  pub fn activate_main_menu_item(_: MenuId) -> bool {
+    false
+  }
+
+  /// Hot keys the system would have registered on this thread, as id, virtual key code and
+  /// Carbon modifiers, sorted by id.
+  pub fn recorded_registrations() -> Vec<(u32, u32, u32)> {
+    let mut registrations: Vec<(u32, u32, u32)> =
+      REGISTERED.with(|registered| registered.borrow().values().copied().collect());
+    registrations.sort_unstable();
+    registrations
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn hot_keys() {
+    let command = NSEventModifierFlags::NSCommandKeyMask;
+    let shift = NSEventModifierFlags::NSShiftKeyMask;
+    let hot_key = |key: &str, masks| {
+      hot_key_of(&OwnedKeyEquivalent {
+        key: key.to_string(),
+        masks,
+      })
+    };
+    assert_eq!(hot_key("s", command), Ok((0x01, CMD_KEY)));
+    // Uppercase implies shift
+    assert_eq!(hot_key("S", command), Ok((0x01, CMD_KEY | SHIFT_KEY)));
+    assert_eq!(hot_key("S", command | shift), hot_key("S", command));
+    assert_eq!(
+      hot_key(
+        "\u{f704}",
+        NSEventModifierFlags::NSControlKeyMask | NSEventModifierFlags::NSAlternateKeyMask
+      ),
+      Ok((0x7A, CONTROL_KEY | OPTION_KEY))
+    );
+    for masks in [NSEventModifierFlags::empty(), shift] {
+      assert_eq!(
+        hot_key("s", masks),
+        Err(GlobalShortcutError::MissingModifier)
+      );
+    }
+    for key in ["é", "ab", ""] {
+      assert_eq!(
+        hot_key(key, command),
+        Err(GlobalShortcutError::UnsupportedKey(key.to_string()))
+      );
+    }
+  }
+
+  #[test]
+  #[cfg(not(target_os = "macos"))]
+  fn registrations() {
+    use crate::event_channel::subscribe;
+
+    let command = NSEventModifierFlags::NSCommandKeyMask;
+    let shortcut = |key, masks| KeyEquivalent {
+      key,
+      masks: Some(masks),
+    };
+    // Registrations are per thread, events aren't
+    let menu_id = MenuId(600);
+    let events = subscribe();
+    register(menu_id, shortcut("s", command)).unwrap();
+    register(menu_id, shortcut("s", command)).unwrap();
+    register(menu_id, shortcut("d", command)).unwrap();
+    assert_eq!(recorded_registrations(), vec![(600, 0x02, CMD_KEY)]);
+    assert_eq!(
+      registered(),
+      vec![(
+        menu_id,
+        OwnedKeyEquivalent {
+          key: "d".to_string(),
+          masks: command,
+        }
+      )]
+    );
+    assert_eq!(
+      register(menu_id, shortcut("f", NSEventModifierFlags::NSShiftKeyMask)),
+      Err(GlobalShortcutError::MissingModifier)
+    );
+    assert_eq!(registered()[0].1.key, "d");
+
+    simulate_press(menu_id);
+    simulate_press(MenuId(601));
+    let pressed: Vec<(MenuId, MenuTrigger)> = events
+      .try_iter()
+      .filter_map(|event| match event {
+        Event::MenuEvent {
+          menu_id, trigger, ..
+        } if (600..700).contains(&menu_id.0) => Some((menu_id, trigger)),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(pressed, vec![(menu_id, MenuTrigger::Keyboard)]);
+
+    assert_eq!(unregister(menu_id), Ok(true));
+    assert_eq!(unregister(menu_id), Ok(false));
+    assert!(recorded_registrations().is_empty());
+    assert!(registered().is_empty());
+  }
+}
@@ -1,4 +1,25 @@
+pub mod accessibility;
+pub mod chord;
+pub mod error;
 pub mod event;
 pub mod event_channel;
+#[cfg(feature = "async")]
+pub mod event_stream;
+pub mod global_shortcut;
+pub mod localization;
+#[cfg(target_os = "macos")]
 pub mod menu;
+pub mod menu_binding;
+#[cfg(target_os = "macos")]
+pub mod menu_font;
+#[cfg(target_os = "macos")]
+pub mod menu_handle;
+pub mod menu_index;
+#[cfg(target_os = "macos")]
+pub mod menu_item_view;
+pub mod menu_spec;
 pub mod platform_impl;
+pub mod shortcut_map;
+pub mod snapshot;
+pub mod standard_menu;
+pub mod validation;
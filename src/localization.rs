@@ -0,0 +1,680 @@
+use crate::{
+  platform_impl::native_menu_item_type::NativeMenuItemType, standard_menu::StandardMenu,
+};
+#[cfg(target_os = "macos")]
+use cocoa::{
+  base::id,
+  foundation::{NSArray, NSString},
+};
+#[cfg(target_os = "macos")]
+use objc::{class, msg_send, sel, sel_impl};
+use once_cell::sync::OnceCell;
+#[cfg(target_os = "macos")]
+use std::ffi::CStr;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Languages with bundled translations of the default native item titles.
+///
+/// Every locale is matched exhaustively against `NativeMenuItemType`,
+/// so a new variant can't be added without a title in each language.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Locale {
+  #[default]
+  English,
+  French,
+  German,
+  Spanish,
+  Japanese,
+  ChineseSimplified,
+}
+
+impl Locale {
+  pub const ALL: [Locale; 6] = [
+    Locale::English,
+    Locale::French,
+    Locale::German,
+    Locale::Spanish,
+    Locale::Japanese,
+    Locale::ChineseSimplified,
+  ];
+
+  /// Match a BCP 47 language tag such as "fr" or "zh-Hans-CN" to a bundled locale.
+  pub fn from_language_tag(tag: &str) -> Option<Locale> {
+    let tag = tag.replace('_', "-").to_lowercase();
+    let mut subtags = tag.split('-');
+    match subtags.next()? {
+      "en" => Some(Locale::English),
+      "fr" => Some(Locale::French),
+      "de" => Some(Locale::German),
+      "es" => Some(Locale::Spanish),
+      "ja" => Some(Locale::Japanese),
+      "zh" => match subtags.next() {
+        Some("hant") | Some("tw") | Some("hk") | Some("mo") => None,
+        _ => Some(Locale::ChineseSimplified),
+      },
+      _ => None,
+    }
+  }
+
+  /// The first preferred language of the user with a bundled translation, English otherwise.
+  #[cfg(target_os = "macos")]
+  pub fn system() -> Locale {
+    unsafe {
+      let languages: id = msg_send![class!(NSLocale), preferredLanguages];
+      for i in 0..languages.count() {
+        let language = languages.objectAtIndex(i);
+        let tag = CStr::from_ptr(language.UTF8String()).to_string_lossy();
+        if let Some(locale) = Locale::from_language_tag(&tag) {
+          return locale;
+        }
+      }
+      Locale::English
+    }
+  }
+}
+
+/// Lookup of default native item titles provided by the application.
+///
+/// Return None to fall back to the bundled translation of the current locale.
+pub trait Localizer: Send + Sync {
+  fn title(&self, item: &NativeMenuItemType, locale: Locale) -> Option<String>;
+  /// Title of `Hide` and `Quit` in the application menu, which includes the app name.
+  fn app_item_title(
+    &self,
+    _item: &NativeMenuItemType,
+    _app_name: &str,
+    _locale: Locale,
+  ) -> Option<String> {
+    None
+  }
+  /// Title of a submenu of the standard application menu.
+  fn menu_title(&self, _menu: StandardMenu, _app_name: &str, _locale: Locale) -> Option<String> {
+    None
+  }
+}
+
+impl<F> Localizer for F
+where
+  F: Fn(&NativeMenuItemType, Locale) -> Option<String> + Send + Sync,
+{
+  fn title(&self, item: &NativeMenuItemType, locale: Locale) -> Option<String> {
+    self(item, locale)
+  }
+}
+
+#[derive(Default)]
+struct Localization {
+  locale: Locale,
+  localizer: Option<Arc<dyn Localizer>>,
+}
+
+fn get_localization() -> &'static RwLock<Localization> {
+  static LOCALIZATION: OnceCell<RwLock<Localization>> = OnceCell::new();
+  LOCALIZATION.get_or_init(|| RwLock::new(Localization::default()))
+}
+
+/// Set the language of the default native item titles. Default to English.
+///
+/// Only applies to items created afterwards.
+pub fn set_locale(locale: Locale) {
+  get_localization()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .locale = locale;
+}
+
+pub fn get_locale() -> Locale {
+  get_localization()
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .locale
+}
+
+/// Set a lookup consulted before the bundled translations.
+pub fn set_localizer<L: Localizer + 'static>(localizer: L) {
+  get_localization()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .localizer = Some(Arc::new(localizer));
+}
+
+pub fn clear_localizer() {
+  get_localization()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .localizer = None;
+}
+
+fn current_localization() -> (Locale, Option<Arc<dyn Localizer>>) {
+  let localization = get_localization()
+    .read()
+    .unwrap_or_else(PoisonError::into_inner);
+  (localization.locale, localization.localizer.clone())
+}
+
+/// Default title of a native item in the current locale.
+pub fn localized_title(item: &NativeMenuItemType) -> String {
+  let (locale, localizer) = current_localization();
+  localizer
+    .and_then(|localizer| localizer.title(item, locale))
+    .unwrap_or_else(|| bundled_title(item, locale))
+}
+
+/// Title of a native item of the application menu in the current locale, such as "Quit MyApp".
+///
+/// Only `Hide` and `Quit` include the app name, other items get their `localized_title`.
+pub fn localized_app_item_title(item: &NativeMenuItemType, app_name: &str) -> String {
+  let (locale, localizer) = current_localization();
+  localizer
+    .and_then(|localizer| localizer.app_item_title(item, app_name, locale))
+    .or_else(|| bundled_app_item_title(item, app_name, locale))
+    .unwrap_or_else(|| localized_title(item))
+}
+
+/// Title of a submenu of the standard application menu in the current locale.
+pub fn localized_menu_title(menu: StandardMenu, app_name: &str) -> String {
+  let (locale, localizer) = current_localization();
+  localizer
+    .and_then(|localizer| localizer.menu_title(menu, app_name, locale))
+    .unwrap_or_else(|| bundled_menu_title(menu, app_name, locale))
+}
+
+/// Bundled title of `Hide` and `Quit` with the app name, None for other items.
+pub fn bundled_app_item_title(
+  item: &NativeMenuItemType,
+  app_name: &str,
+  locale: Locale,
+) -> Option<String> {
+  let title = match (item, locale) {
+    (NativeMenuItemType::Hide, Locale::English) => format!("Hide {}", app_name),
+    (NativeMenuItemType::Hide, Locale::French) => format!("Masquer {}", app_name),
+    (NativeMenuItemType::Hide, Locale::German) => format!("{} ausblenden", app_name),
+    (NativeMenuItemType::Hide, Locale::Spanish) => format!("Ocultar {}", app_name),
+    (NativeMenuItemType::Hide, Locale::Japanese) => format!("{}を隠す", app_name),
+    (NativeMenuItemType::Hide, Locale::ChineseSimplified) => format!("隐藏“{}”", app_name),
+    (NativeMenuItemType::Quit, Locale::English) => format!("Quit {}", app_name),
+    (NativeMenuItemType::Quit, Locale::French) => format!("Quitter {}", app_name),
+    (NativeMenuItemType::Quit, Locale::German) => format!("{} beenden", app_name),
+    (NativeMenuItemType::Quit, Locale::Spanish) => format!("Salir de {}", app_name),
+    (NativeMenuItemType::Quit, Locale::Japanese) => format!("{}を終了", app_name),
+    (NativeMenuItemType::Quit, Locale::ChineseSimplified) => format!("退出“{}”", app_name),
+    _ => return None,
+  };
+  Some(title)
+}
+
+/// Bundled title of a submenu of the standard application menu.
+/// The application menu is always titled with the app name.
+pub fn bundled_menu_title(menu: StandardMenu, app_name: &str, locale: Locale) -> String {
+  let title = match (menu, locale) {
+    (StandardMenu::App, _) => app_name,
+    (StandardMenu::File, Locale::English) => "File",
+    (StandardMenu::File, Locale::French) => "Fichier",
+    (StandardMenu::File, Locale::German) => "Ablage",
+    (StandardMenu::File, Locale::Spanish) => "Archivo",
+    (StandardMenu::File, Locale::Japanese) => "ファイル",
+    (StandardMenu::File, Locale::ChineseSimplified) => "文件",
+    (StandardMenu::Edit, Locale::English) => "Edit",
+    (StandardMenu::Edit, Locale::French) => "Édition",
+    (StandardMenu::Edit, Locale::German) => "Bearbeiten",
+    (StandardMenu::Edit, Locale::Spanish) => "Edición",
+    (StandardMenu::Edit, Locale::Japanese) => "編集",
+    (StandardMenu::Edit, Locale::ChineseSimplified) => "编辑",
+    (StandardMenu::Find, Locale::English) => "Find",
+    (StandardMenu::Find, Locale::French) => "Rechercher",
+    (StandardMenu::Find, Locale::German) => "Suchen",
+    (StandardMenu::Find, Locale::Spanish) => "Buscar",
+    (StandardMenu::Find, Locale::Japanese) => "検索",
+    (StandardMenu::Find, Locale::ChineseSimplified) => "查找",
+    (StandardMenu::View, Locale::English) => "View",
+    (StandardMenu::View, Locale::French) => "Présentation",
+    (StandardMenu::View, Locale::German) => "Darstellung",
+    (StandardMenu::View, Locale::Spanish) => "Visualización",
+    (StandardMenu::View, Locale::Japanese) => "表示",
+    (StandardMenu::View, Locale::ChineseSimplified) => "显示",
+    (StandardMenu::Window, Locale::English) => "Window",
+    (StandardMenu::Window, Locale::French) => "Fenêtre",
+    (StandardMenu::Window, Locale::German) => "Fenster",
+    (StandardMenu::Window, Locale::Spanish) => "Ventana",
+    (StandardMenu::Window, Locale::Japanese) => "ウインドウ",
+    (StandardMenu::Window, Locale::ChineseSimplified) => "窗口",
+    (StandardMenu::Help, Locale::English) => "Help",
+    (StandardMenu::Help, Locale::French) => "Aide",
+    (StandardMenu::Help, Locale::German) => "Hilfe",
+    (StandardMenu::Help, Locale::Spanish) => "Ayuda",
+    (StandardMenu::Help, Locale::Japanese) => "ヘルプ",
+    (StandardMenu::Help, Locale::ChineseSimplified) => "帮助",
+  };
+  title.to_string()
+}
+
+/// Default title of a native item from the bundled translations.
+pub fn bundled_title(item: &NativeMenuItemType, locale: Locale) -> String {
+  match locale {
+    Locale::English => english_title(item),
+    Locale::French => french_title(item),
+    Locale::German => german_title(item),
+    Locale::Spanish => spanish_title(item),
+    Locale::Japanese => japanese_title(item),
+    Locale::ChineseSimplified => chinese_simplified_title(item),
+  }
+}
+
+fn english_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("About {}", app_name),
+    NativeMenuItemType::CloseWindow => "Close Window".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "Quit".into(),
+    NativeMenuItemType::Hide => "Hide".into(),
+    NativeMenuItemType::HideOthers => "Hide Others".into(),
+    NativeMenuItemType::ShowAll => "Show All".into(),
+    NativeMenuItemType::EnterFullScreen => "Enter Full Screen".into(),
+    NativeMenuItemType::Minimize => "Minimize".into(),
+    NativeMenuItemType::Zoom => "Zoom".into(),
+    NativeMenuItemType::Copy => "Copy".into(),
+    NativeMenuItemType::Cut => "Cut".into(),
+    NativeMenuItemType::Paste => "Paste".into(),
+    NativeMenuItemType::Undo => "Undo".into(),
+    NativeMenuItemType::Redo => "Redo".into(),
+    NativeMenuItemType::SelectAll => "Select All".into(),
+    NativeMenuItemType::Services => "Services".into(),
+    NativeMenuItemType::BringAllToFront => "Bring All to Front".into(),
+    NativeMenuItemType::Preferences => "Preferences…".into(),
+    NativeMenuItemType::Help(app_name) => format!("{} Help", app_name),
+    NativeMenuItemType::Find => "Find…".into(),
+    NativeMenuItemType::FindNext => "Find Next".into(),
+    NativeMenuItemType::FindPrevious => "Find Previous".into(),
+    NativeMenuItemType::Delete => "Delete".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "Paste and Match Style".into(),
+    NativeMenuItemType::ToggleTabBar => "Show Tab Bar".into(),
+    NativeMenuItemType::SelectNextTab => "Show Next Tab".into(),
+    NativeMenuItemType::SelectPreviousTab => "Show Previous Tab".into(),
+    NativeMenuItemType::ToggleSidebar => "Show Sidebar".into(),
+    NativeMenuItemType::PageSetup => "Page Setup…".into(),
+    NativeMenuItemType::Print => "Print…".into(),
+  }
+}
+
+fn french_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("À propos de {}", app_name),
+    NativeMenuItemType::CloseWindow => "Fermer la fenêtre".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "Quitter".into(),
+    NativeMenuItemType::Hide => "Masquer".into(),
+    NativeMenuItemType::HideOthers => "Masquer les autres".into(),
+    NativeMenuItemType::ShowAll => "Tout afficher".into(),
+    NativeMenuItemType::EnterFullScreen => "Passer en mode plein écran".into(),
+    NativeMenuItemType::Minimize => "Placer dans le Dock".into(),
+    NativeMenuItemType::Zoom => "Réduire/agrandir".into(),
+    NativeMenuItemType::Copy => "Copier".into(),
+    NativeMenuItemType::Cut => "Couper".into(),
+    NativeMenuItemType::Paste => "Coller".into(),
+    NativeMenuItemType::Undo => "Annuler".into(),
+    NativeMenuItemType::Redo => "Rétablir".into(),
+    NativeMenuItemType::SelectAll => "Tout sélectionner".into(),
+    NativeMenuItemType::Services => "Services".into(),
+    NativeMenuItemType::BringAllToFront => "Tout ramener au premier plan".into(),
+    NativeMenuItemType::Preferences => "Préférences…".into(),
+    NativeMenuItemType::Help(app_name) => format!("Aide {}", app_name),
+    NativeMenuItemType::Find => "Rechercher…".into(),
+    NativeMenuItemType::FindNext => "Rechercher le suivant".into(),
+    NativeMenuItemType::FindPrevious => "Rechercher le précédent".into(),
+    NativeMenuItemType::Delete => "Supprimer".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "Coller et adapter le style".into(),
+    NativeMenuItemType::ToggleTabBar => "Afficher la barre des onglets".into(),
+    NativeMenuItemType::SelectNextTab => "Afficher l’onglet suivant".into(),
+    NativeMenuItemType::SelectPreviousTab => "Afficher l’onglet précédent".into(),
+    NativeMenuItemType::ToggleSidebar => "Afficher la barre latérale".into(),
+    NativeMenuItemType::PageSetup => "Format d’impression…".into(),
+    NativeMenuItemType::Print => "Imprimer…".into(),
+  }
+}
+
+fn german_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("Über {}", app_name),
+    NativeMenuItemType::CloseWindow => "Fenster schließen".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "Beenden".into(),
+    NativeMenuItemType::Hide => "Ausblenden".into(),
+    NativeMenuItemType::HideOthers => "Andere ausblenden".into(),
+    NativeMenuItemType::ShowAll => "Alle einblenden".into(),
+    NativeMenuItemType::EnterFullScreen => "Vollbildmodus aktivieren".into(),
+    NativeMenuItemType::Minimize => "Im Dock ablegen".into(),
+    NativeMenuItemType::Zoom => "Zoomen".into(),
+    NativeMenuItemType::Copy => "Kopieren".into(),
+    NativeMenuItemType::Cut => "Ausschneiden".into(),
+    NativeMenuItemType::Paste => "Einsetzen".into(),
+    NativeMenuItemType::Undo => "Widerrufen".into(),
+    NativeMenuItemType::Redo => "Wiederholen".into(),
+    NativeMenuItemType::SelectAll => "Alles auswählen".into(),
+    NativeMenuItemType::Services => "Dienste".into(),
+    NativeMenuItemType::BringAllToFront => "Alle nach vorne bringen".into(),
+    NativeMenuItemType::Preferences => "Einstellungen …".into(),
+    NativeMenuItemType::Help(app_name) => format!("{}-Hilfe", app_name),
+    NativeMenuItemType::Find => "Suchen …".into(),
+    NativeMenuItemType::FindNext => "Weitersuchen".into(),
+    NativeMenuItemType::FindPrevious => "Rückwärts suchen".into(),
+    NativeMenuItemType::Delete => "Löschen".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "Einsetzen und Stil anpassen".into(),
+    NativeMenuItemType::ToggleTabBar => "Tableiste einblenden".into(),
+    NativeMenuItemType::SelectNextTab => "Nächsten Tab anzeigen".into(),
+    NativeMenuItemType::SelectPreviousTab => "Vorherigen Tab anzeigen".into(),
+    NativeMenuItemType::ToggleSidebar => "Seitenleiste einblenden".into(),
+    NativeMenuItemType::PageSetup => "Papierformat …".into(),
+    NativeMenuItemType::Print => "Drucken …".into(),
+  }
+}
+
+fn spanish_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("Acerca de {}", app_name),
+    NativeMenuItemType::CloseWindow => "Cerrar ventana".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "Salir".into(),
+    NativeMenuItemType::Hide => "Ocultar".into(),
+    NativeMenuItemType::HideOthers => "Ocultar otros".into(),
+    NativeMenuItemType::ShowAll => "Mostrar todo".into(),
+    NativeMenuItemType::EnterFullScreen => "Usar pantalla completa".into(),
+    NativeMenuItemType::Minimize => "Minimizar".into(),
+    NativeMenuItemType::Zoom => "Zoom".into(),
+    NativeMenuItemType::Copy => "Copiar".into(),
+    NativeMenuItemType::Cut => "Cortar".into(),
+    NativeMenuItemType::Paste => "Pegar".into(),
+    NativeMenuItemType::Undo => "Deshacer".into(),
+    NativeMenuItemType::Redo => "Rehacer".into(),
+    NativeMenuItemType::SelectAll => "Seleccionar todo".into(),
+    NativeMenuItemType::Services => "Servicios".into(),
+    NativeMenuItemType::BringAllToFront => "Traer todo al frente".into(),
+    NativeMenuItemType::Preferences => "Preferencias…".into(),
+    NativeMenuItemType::Help(app_name) => format!("Ayuda de {}", app_name),
+    NativeMenuItemType::Find => "Buscar…".into(),
+    NativeMenuItemType::FindNext => "Buscar siguiente".into(),
+    NativeMenuItemType::FindPrevious => "Buscar anterior".into(),
+    NativeMenuItemType::Delete => "Eliminar".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "Pegar con el mismo estilo".into(),
+    NativeMenuItemType::ToggleTabBar => "Mostrar barra de pestañas".into(),
+    NativeMenuItemType::SelectNextTab => "Mostrar pestaña siguiente".into(),
+    NativeMenuItemType::SelectPreviousTab => "Mostrar pestaña anterior".into(),
+    NativeMenuItemType::ToggleSidebar => "Mostrar barra lateral".into(),
+    NativeMenuItemType::PageSetup => "Ajustar página…".into(),
+    NativeMenuItemType::Print => "Imprimir…".into(),
+  }
+}
+
+fn japanese_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("{}について", app_name),
+    NativeMenuItemType::CloseWindow => "ウインドウを閉じる".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "終了".into(),
+    NativeMenuItemType::Hide => "隠す".into(),
+    NativeMenuItemType::HideOthers => "ほかを隠す".into(),
+    NativeMenuItemType::ShowAll => "すべてを表示".into(),
+    NativeMenuItemType::EnterFullScreen => "フルスクリーンにする".into(),
+    NativeMenuItemType::Minimize => "しまう".into(),
+    NativeMenuItemType::Zoom => "拡大/縮小".into(),
+    NativeMenuItemType::Copy => "コピー".into(),
+    NativeMenuItemType::Cut => "カット".into(),
+    NativeMenuItemType::Paste => "ペースト".into(),
+    NativeMenuItemType::Undo => "取り消す".into(),
+    NativeMenuItemType::Redo => "やり直す".into(),
+    NativeMenuItemType::SelectAll => "すべてを選択".into(),
+    NativeMenuItemType::Services => "サービス".into(),
+    NativeMenuItemType::BringAllToFront => "すべてを手前に移動".into(),
+    NativeMenuItemType::Preferences => "環境設定…".into(),
+    NativeMenuItemType::Help(app_name) => format!("{}ヘルプ", app_name),
+    NativeMenuItemType::Find => "検索…".into(),
+    NativeMenuItemType::FindNext => "次を検索".into(),
+    NativeMenuItemType::FindPrevious => "前を検索".into(),
+    NativeMenuItemType::Delete => "削除".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "ペーストしてスタイルを合わせる".into(),
+    NativeMenuItemType::ToggleTabBar => "タブバーを表示".into(),
+    NativeMenuItemType::SelectNextTab => "次のタブを表示".into(),
+    NativeMenuItemType::SelectPreviousTab => "前のタブを表示".into(),
+    NativeMenuItemType::ToggleSidebar => "サイドバーを表示".into(),
+    NativeMenuItemType::PageSetup => "ページ設定…".into(),
+    NativeMenuItemType::Print => "プリント…".into(),
+  }
+}
+
+fn chinese_simplified_title(item: &NativeMenuItemType) -> String {
+  match item {
+    NativeMenuItemType::About(app_name) => format!("关于{}", app_name),
+    NativeMenuItemType::CloseWindow => "关闭窗口".into(),
+    NativeMenuItemType::Separator => "".into(),
+    NativeMenuItemType::Quit => "退出".into(),
+    NativeMenuItemType::Hide => "隐藏".into(),
+    NativeMenuItemType::HideOthers => "隐藏其他".into(),
+    NativeMenuItemType::ShowAll => "全部显示".into(),
+    NativeMenuItemType::EnterFullScreen => "进入全屏幕".into(),
+    NativeMenuItemType::Minimize => "最小化".into(),
+    NativeMenuItemType::Zoom => "缩放".into(),
+    NativeMenuItemType::Copy => "拷贝".into(),
+    NativeMenuItemType::Cut => "剪切".into(),
+    NativeMenuItemType::Paste => "粘贴".into(),
+    NativeMenuItemType::Undo => "撤销".into(),
+    NativeMenuItemType::Redo => "重做".into(),
+    NativeMenuItemType::SelectAll => "全选".into(),
+    NativeMenuItemType::Services => "服务".into(),
+    NativeMenuItemType::BringAllToFront => "前置全部窗口".into(),
+    NativeMenuItemType::Preferences => "偏好设置…".into(),
+    NativeMenuItemType::Help(app_name) => format!("{}帮助", app_name),
+    NativeMenuItemType::Find => "查找…".into(),
+    NativeMenuItemType::FindNext => "查找下一个".into(),
+    NativeMenuItemType::FindPrevious => "查找上一个".into(),
+    NativeMenuItemType::Delete => "删除".into(),
+    NativeMenuItemType::PasteAndMatchStyle => "粘贴并匹配样式".into(),
+    NativeMenuItemType::ToggleTabBar => "显示标签页栏".into(),
+    NativeMenuItemType::SelectNextTab => "显示下一个标签页".into(),
+    NativeMenuItemType::SelectPreviousTab => "显示上一个标签页".into(),
+    NativeMenuItemType::ToggleSidebar => "显示边栏".into(),
+    NativeMenuItemType::PageSetup => "页面设置…".into(),
+    NativeMenuItemType::Print => "打印…".into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+
+  const APP_NAME: &str = "Demo";
+
+  fn all_item_types() -> Vec<NativeMenuItemType> {
+    let items = vec![
+      NativeMenuItemType::About(APP_NAME.to_string()),
+      NativeMenuItemType::CloseWindow,
+      NativeMenuItemType::Separator,
+      NativeMenuItemType::Quit,
+      NativeMenuItemType::Hide,
+      NativeMenuItemType::HideOthers,
+      NativeMenuItemType::ShowAll,
+      NativeMenuItemType::EnterFullScreen,
+      NativeMenuItemType::Minimize,
+      NativeMenuItemType::Zoom,
+      NativeMenuItemType::Copy,
+      NativeMenuItemType::Cut,
+      NativeMenuItemType::Paste,
+      NativeMenuItemType::Undo,
+      NativeMenuItemType::Redo,
+      NativeMenuItemType::SelectAll,
+      NativeMenuItemType::Services,
+      NativeMenuItemType::BringAllToFront,
+      NativeMenuItemType::Preferences,
+      NativeMenuItemType::Help(APP_NAME.to_string()),
+      NativeMenuItemType::Find,
+      NativeMenuItemType::FindNext,
+      NativeMenuItemType::FindPrevious,
+      NativeMenuItemType::Delete,
+      NativeMenuItemType::PasteAndMatchStyle,
+      NativeMenuItemType::ToggleTabBar,
+      NativeMenuItemType::SelectNextTab,
+      NativeMenuItemType::SelectPreviousTab,
+      NativeMenuItemType::ToggleSidebar,
+      NativeMenuItemType::PageSetup,
+      NativeMenuItemType::Print,
+    ];
+    // Fails to build when a variant is added, so it gets added to the list above too
+    for item in &items {
+      match item {
+        NativeMenuItemType::About(_)
+        | NativeMenuItemType::CloseWindow
+        | NativeMenuItemType::Separator
+        | NativeMenuItemType::Quit
+        | NativeMenuItemType::Hide
+        | NativeMenuItemType::HideOthers
+        | NativeMenuItemType::ShowAll
+        | NativeMenuItemType::EnterFullScreen
+        | NativeMenuItemType::Minimize
+        | NativeMenuItemType::Zoom
+        | NativeMenuItemType::Copy
+        | NativeMenuItemType::Cut
+        | NativeMenuItemType::Paste
+        | NativeMenuItemType::Undo
+        | NativeMenuItemType::Redo
+        | NativeMenuItemType::SelectAll
+        | NativeMenuItemType::Services
+        | NativeMenuItemType::BringAllToFront
+        | NativeMenuItemType::Preferences
+        | NativeMenuItemType::Help(_)
+        | NativeMenuItemType::Find
+        | NativeMenuItemType::FindNext
+        | NativeMenuItemType::FindPrevious
+        | NativeMenuItemType::Delete
+        | NativeMenuItemType::PasteAndMatchStyle
+        | NativeMenuItemType::ToggleTabBar
+        | NativeMenuItemType::SelectNextTab
+        | NativeMenuItemType::SelectPreviousTab
+        | NativeMenuItemType::ToggleSidebar
+        | NativeMenuItemType::PageSetup
+        | NativeMenuItemType::Print => (),
+      }
+    }
+    items
+  }
+
+  // Translations spelled the same as in English
+  fn same_as_english(item: &NativeMenuItemType, locale: Locale) -> bool {
+    matches!(
+      (item, locale),
+      (NativeMenuItemType::Services, Locale::French) | (NativeMenuItemType::Zoom, Locale::Spanish)
+    )
+  }
+
+  #[test]
+  fn every_locale_translates_every_item() {
+    for locale in Locale::ALL {
+      for item in all_item_types() {
+        let title = bundled_title(&item, locale);
+        if item == NativeMenuItemType::Separator {
+          assert_eq!(title, "");
+          continue;
+        }
+        assert!(!title.trim().is_empty(), "{:?} {:?}", locale, item);
+        if locale != Locale::English && !same_as_english(&item, locale) {
+          assert_ne!(
+            title,
+            bundled_title(&item, Locale::English),
+            "{:?} {:?}",
+            locale,
+            item
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn app_name_is_kept() {
+    for locale in Locale::ALL {
+      for item in [
+        NativeMenuItemType::About(APP_NAME.to_string()),
+        NativeMenuItemType::Help(APP_NAME.to_string()),
+      ] {
+        assert!(bundled_title(&item, locale).contains(APP_NAME));
+      }
+      for item in [NativeMenuItemType::Hide, NativeMenuItemType::Quit] {
+        let title = bundled_app_item_title(&item, APP_NAME, locale).unwrap();
+        assert!(title.contains(APP_NAME), "{:?} {:?}", locale, item);
+        if locale != Locale::English {
+          assert_ne!(
+            Some(title),
+            bundled_app_item_title(&item, APP_NAME, Locale::English)
+          );
+        }
+      }
+      assert_eq!(
+        bundled_app_item_title(&NativeMenuItemType::Copy, APP_NAME, locale),
+        None
+      );
+    }
+  }
+
+  #[test]
+  fn every_locale_translates_every_menu() {
+    let menus = [
+      StandardMenu::File,
+      StandardMenu::Edit,
+      StandardMenu::Find,
+      StandardMenu::View,
+      StandardMenu::Window,
+      StandardMenu::Help,
+    ];
+    for locale in Locale::ALL {
+      assert_eq!(
+        bundled_menu_title(StandardMenu::App, APP_NAME, locale),
+        APP_NAME
+      );
+      for menu in menus {
+        let title = bundled_menu_title(menu, APP_NAME, locale);
+        assert!(!title.is_empty());
+        if locale != Locale::English {
+          assert_ne!(
+            title,
+            bundled_menu_title(menu, APP_NAME, Locale::English),
+            "{:?} {:?}",
+            locale,
+            menu
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn language_tags() {
+    assert_eq!(Locale::from_language_tag("fr"), Some(Locale::French));
+    assert_eq!(Locale::from_language_tag("de_CH"), Some(Locale::German));
+    assert_eq!(
+      Locale::from_language_tag("zh-Hans-CN"),
+      Some(Locale::ChineseSimplified)
+    );
+    assert_eq!(Locale::from_language_tag("zh-Hant-TW"), None);
+    assert_eq!(Locale::from_language_tag("it"), None);
+  }
+
+  // The localization is global, so everything runs in a single test
+  #[test]
+  fn localization_survives_panics() {
+    set_localizer(|item: &NativeMenuItemType, _| match item {
+      NativeMenuItemType::Copy => panic!("broken localizer"),
+      NativeMenuItemType::Cut => Some("Snip".to_string()),
+      _ => None,
+    });
+    assert!(catch_unwind(|| localized_title(&NativeMenuItemType::Copy)).is_err());
+    assert_eq!(localized_title(&NativeMenuItemType::Cut), "Snip");
+
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+      let _guard = get_localization().write().unwrap();
+      panic!("poison the lock");
+    }));
+    assert!(get_localization().is_poisoned());
+    assert_eq!(localized_title(&NativeMenuItemType::Cut), "Snip");
+    assert_eq!(get_locale(), Locale::English);
+    clear_localizer();
+    assert_eq!(localized_title(&NativeMenuItemType::Cut), "Cut");
+  }
+}
@@ -1,81 +1,252 @@
-use crate::platform_impl::{
-  key, menu::MenuType, menu_item::MenuItem, menu_item_attributes::MenuItemAttributes,
-  native_menu_item_type::NativeMenuItemType,
+use crate::{
+  error::MenuError,
+  platform_impl::{
+    key,
+    menu::{
+      assert_main_thread, is_main_thread, ns_string_to_string, set_menu_role, MenuId, MenuRole,
+      MenuType,
+    },
+    menu_item::{activate_menu_item, find_menu_item, MenuItem},
+    menu_item_attributes::MenuItemAttributes,
+    native_menu_item_type::NativeMenuItemType,
+    retained::Retained,
+  },
 };
 use cocoa::{
   appkit::{NSApp, NSApplication, NSMenu, NSMenuItem},
-  base::{id, nil, NO},
-  foundation::{NSAutoreleasePool, NSString},
+  base::{id, nil, NO, YES},
+  foundation::NSString,
 };
 use objc::{msg_send, sel, sel_impl};
+use std::vec;
 
 pub fn set_menu(menu: &Menu) {
   unsafe {
     let app = NSApp();
-    app.setMainMenu_(menu.ns_menu);
+    app.setMainMenu_(menu.ns_menu.as_ptr());
   }
 }
 
 /// Menu Bar
+///
+/// Only usable on the main thread, use `Menu::handle` to update it from other threads.
 #[derive(Debug, Clone)]
 pub struct Menu {
-  pub ns_menu: id,
+  ns_menu: Retained,
 }
 
 impl Menu {
+  /// # Panics
+  ///
+  /// Panics when not called on the main thread, see `try_new`.
   pub fn new() -> Self {
+    assert_main_thread();
+    Self::try_new().unwrap()
+  }
+  /// Like `new`, returning `MenuError::NotMainThread` instead of panicking.
+  pub fn try_new() -> Result<Self, MenuError> {
+    Ok(Self {
+      ns_menu: make_ns_menu()?,
+    })
+  }
+  /// Get the underlying `NSMenu`.
+  ///
+  /// # Safety
+  ///
+  /// The pointer is only valid as long as this menu, or one of its clones, is alive.
+  /// Retain it to keep it longer.
+  pub unsafe fn as_raw(&self) -> id {
+    self.ns_menu.as_ptr()
+  }
+  /// Wrap a menu read back from an item.
+  pub(crate) unsafe fn from_raw(ns_menu: id) -> Self {
+    Self {
+      ns_menu: Retained::retain(ns_menu),
+    }
+  }
+  pub fn title(&self) -> String {
     unsafe {
-      let ns_menu = NSMenu::alloc(nil).autorelease();
-      let () = msg_send![ns_menu, setAutoenablesItems: NO];
-      Self { ns_menu }
+      let title: id = msg_send![self.ns_menu.as_ptr(), title];
+      ns_string_to_string(title)
+    }
+  }
+  /// Items of this menu, without the items of its submenus.
+  pub fn items(&self) -> Vec<MenuItem> {
+    unsafe {
+      let count: isize = msg_send![self.ns_menu.as_ptr(), numberOfItems];
+      (0..count)
+        .map(|index| {
+          let item: id = msg_send![self.ns_menu.as_ptr(), itemAtIndex: index];
+          MenuItem::from_raw(item)
+        })
+        .collect()
+    }
+  }
+  /// Depth first iterator over the items of this menu and its submenus.
+  /// An item holding a submenu comes right before the items of the submenu.
+  pub fn iter(&self) -> MenuIter {
+    MenuIter {
+      stack: vec![self.items().into_iter()],
+    }
+  }
+  /// Find a custom item by id, searching submenus too.
+  pub fn find_by_id(&self, menu_id: MenuId) -> Option<MenuItem> {
+    unsafe { find_menu_item(self.ns_menu.as_ptr(), menu_id).map(|item| MenuItem::from_raw(item)) }
+  }
+  /// Find an item by the titles leading to it, such as `&["File", "Export", "PNG"]`.
+  ///
+  /// When several items have the same title, the first one is returned, and the first one
+  /// holding a submenu is followed.
+  pub fn find_by_title_path(&self, path: &[&str]) -> Option<MenuItem> {
+    let (title, submenus) = path.split_last()?;
+    let mut menu = self.clone();
+    for submenu_title in submenus {
+      menu = menu
+        .items()
+        .into_iter()
+        .filter(|item| item.title() == *submenu_title)
+        .find_map(|item| item.submenu())?;
     }
+    menu.items().into_iter().find(|item| item.title() == *title)
   }
   /// Add a custom menu item to the menu
-  pub fn add_item(&self, attributes: MenuItemAttributes) -> MenuItem {
-    let menu_item = MenuItem::new(
-      attributes.title,
-      attributes.selector,
-      attributes.key_equivalent,
-      attributes.enabled,
-      attributes.selected,
-      MenuType::MenuBar,
-    );
+  pub fn add_item(&self, attributes: MenuItemAttributes) -> Result<MenuItem, MenuError> {
+    let menu_item = MenuItem::from_attributes(attributes, MenuType::MenuBar)?;
+    unsafe {
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(menu_item.ns_menu_item.as_ptr());
+    }
+    Ok(menu_item)
+  }
+  /// Insert a custom menu item at `index`, or at the end if `index` is out of bounds.
+  pub fn insert_item(
+    &self,
+    index: usize,
+    attributes: MenuItemAttributes,
+  ) -> Result<MenuItem, MenuError> {
+    let menu_item = MenuItem::from_attributes(attributes, MenuType::MenuBar)?;
+    unsafe {
+      let count: isize = msg_send![self.ns_menu.as_ptr(), numberOfItems];
+      let index = (index as isize).min(count);
+      let () = msg_send![self.ns_menu.as_ptr(), insertItem: menu_item.ns_menu_item.as_ptr() atIndex: index];
+    }
+    Ok(menu_item)
+  }
+  /// Remove an item from the menu. Does nothing if the item is not in this menu.
+  pub fn remove_item(&self, item: &MenuItem) {
     unsafe {
-      self.ns_menu.addItem_(menu_item.ns_menu_item);
+      let index: isize = msg_send![self.ns_menu.as_ptr(), indexOfItem: item.ns_menu_item.as_ptr()];
+      // -1 when the item is not found
+      if index >= 0 {
+        let () = msg_send![self.ns_menu.as_ptr(), removeItemAtIndex: index];
+      }
     }
-    menu_item
   }
   /// Add predefined menu item to the menu. Such as about, hide, quit, etc.
   /// title and key_equivalent are optional, leave None to use default configuration.
   ///
-  /// Note that default title is only available in English.
+  /// Default title follows the locale set with `localization::set_locale`.
   pub fn add_native_item(
     &self,
     item: NativeMenuItemType,
     title: Option<&str>,
     key_equivalent: Option<key::KeyEquivalent>,
-  ) -> MenuItem {
-    let native_menu_item = MenuItem::new_native(item, title, key_equivalent, MenuType::MenuBar);
+  ) -> Result<MenuItem, MenuError> {
+    let native_menu_item = MenuItem::new_native(item, title, key_equivalent, MenuType::MenuBar)?;
     unsafe {
-      self.ns_menu.addItem_(native_menu_item.ns_menu_item);
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(native_menu_item.ns_menu_item.as_ptr());
     }
-    native_menu_item
+    Ok(native_menu_item)
   }
-  pub fn add_submenu(&self, submenu: &Menu, title: &str) -> MenuItem {
-    submenu.set_title(title);
+  pub fn add_submenu(&self, submenu: &Menu, title: &str) -> Result<MenuItem, MenuError> {
+    let menu_item = submenu.make_submenu_item(title)?;
+    unsafe {
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(menu_item.ns_menu_item.as_ptr());
+    }
+    Ok(menu_item)
+  }
+  /// Item opening this menu, not added to any menu yet.
+  pub(crate) fn make_submenu_item(&self, title: &str) -> Result<MenuItem, MenuError> {
+    self.set_title(title);
 
-    let menu_item = MenuItem::new(title, None, None, true, false, MenuType::MenuBar);
+    let menu_item = MenuItem::new(title, None, None, true, false, MenuType::MenuBar)?;
 
     unsafe {
-      menu_item.ns_menu_item.setSubmenu_(submenu.ns_menu);
-      self.ns_menu.addItem_(menu_item.ns_menu_item);
+      menu_item
+        .ns_menu_item
+        .as_ptr()
+        .setSubmenu_(self.ns_menu.as_ptr());
+    }
+    Ok(menu_item)
+  }
+  /// Trigger the custom item with this id, searching submenus too. See `MenuItem::activate`.
+  ///
+  /// Returns false if no enabled item with this id was found.
+  pub fn perform_action_by_id(&self, menu_id: MenuId) -> bool {
+    unsafe {
+      find_menu_item(self.ns_menu.as_ptr(), menu_id)
+        .map(|item| activate_menu_item(item))
+        .unwrap_or(false)
     }
-    menu_item
   }
   pub fn set_title(&self, title: &str) {
     unsafe {
       let menu_title = NSString::alloc(nil).init_str(title);
-      let () = msg_send![self.ns_menu, setTitle: menu_title];
+      let () = msg_send![self.ns_menu.as_ptr(), setTitle: menu_title];
+    }
+  }
+  /// Register this menu with the system, so macOS can add the window list, the Help search field
+  /// or the available services to it.
+  pub fn set_role(&self, role: MenuRole) {
+    set_menu_role(self.ns_menu.as_ptr(), role);
+  }
+  /// Let the validation handler decide the state of custom items right before the menu is displayed.
+  /// Disabled by default, in which case items keep the state set through `MenuItem`.
+  ///
+  /// Only applies to this menu, turn it on for each submenu as well.
+  /// See `validation::set_validation_handler`.
+  pub fn set_auto_validation(&self, auto_validation: bool) {
+    unsafe {
+      let status = match auto_validation {
+        true => YES,
+        false => NO,
+      };
+      let () = msg_send![self.ns_menu.as_ptr(), setAutoenablesItems: status];
+    }
+  }
+}
+
+/// Depth first iterator over the items of a menu, see `Menu::iter`.
+#[derive(Debug)]
+pub struct MenuIter {
+  stack: Vec<vec::IntoIter<MenuItem>>,
+}
+
+impl Iterator for MenuIter {
+  type Item = MenuItem;
+
+  fn next(&mut self) -> Option<MenuItem> {
+    loop {
+      let items = self.stack.last_mut()?;
+      match items.next() {
+        Some(item) => {
+          if let Some(submenu) = item.submenu() {
+            self.stack.push(submenu.items().into_iter());
+          }
+          return Some(item);
+        }
+        None => {
+          self.stack.pop();
+        }
+      }
     }
   }
 }
@@ -89,55 +260,103 @@ impl Default for Menu {
 // Context Menu
 #[derive(Debug, Clone)]
 pub struct ContextMenu {
-  pub ns_menu: id,
+  ns_menu: Retained,
 }
 
 impl ContextMenu {
+  /// # Panics
+  ///
+  /// Panics when not called on the main thread, see `try_new`.
   pub fn new() -> Self {
-    unsafe {
-      let ns_menu = NSMenu::alloc(nil).autorelease();
-      let () = msg_send![ns_menu, setAutoenablesItems: NO];
-      Self { ns_menu }
-    }
+    assert_main_thread();
+    Self::try_new().unwrap()
+  }
+  /// Like `new`, returning `MenuError::NotMainThread` instead of panicking.
+  pub fn try_new() -> Result<Self, MenuError> {
+    Ok(Self {
+      ns_menu: make_ns_menu()?,
+    })
   }
-  pub fn add_item(&self, attributes: MenuItemAttributes) {
-    let menu_item = MenuItem::new(
-      attributes.title,
-      attributes.selector,
-      attributes.key_equivalent,
-      attributes.enabled,
-      attributes.selected,
-      MenuType::ContextMenu,
-    );
+  /// Get the underlying `NSMenu`.
+  ///
+  /// # Safety
+  ///
+  /// The pointer is only valid as long as this menu, or one of its clones, is alive.
+  /// Retain it to keep it longer.
+  pub unsafe fn as_raw(&self) -> id {
+    self.ns_menu.as_ptr()
+  }
+  pub fn add_item(&self, attributes: MenuItemAttributes) -> Result<(), MenuError> {
+    let menu_item = MenuItem::from_attributes(attributes, MenuType::ContextMenu)?;
     unsafe {
-      self.ns_menu.addItem_(menu_item.ns_menu_item);
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(menu_item.ns_menu_item.as_ptr());
     }
+    Ok(())
   }
   pub fn add_native_item(
     &self,
     item: NativeMenuItemType,
     title: Option<&str>,
     key_equivalent: Option<key::KeyEquivalent>,
-  ) {
-    let native_menu_item = MenuItem::new_native(item, title, key_equivalent, MenuType::ContextMenu);
+  ) -> Result<(), MenuError> {
+    let native_menu_item =
+      MenuItem::new_native(item, title, key_equivalent, MenuType::ContextMenu)?;
     unsafe {
-      self.ns_menu.addItem_(native_menu_item.ns_menu_item);
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(native_menu_item.ns_menu_item.as_ptr());
     }
+    Ok(())
   }
-  pub fn add_submenu(&self, submenu: &ContextMenu, title: &str) {
+  pub fn add_submenu(&self, submenu: &ContextMenu, title: &str) -> Result<(), MenuError> {
     submenu.set_title(title);
 
-    let menu_item = MenuItem::new(title, None, None, true, false, MenuType::ContextMenu);
+    let menu_item = MenuItem::new(title, None, None, true, false, MenuType::ContextMenu)?;
 
     unsafe {
-      menu_item.ns_menu_item.setSubmenu_(submenu.ns_menu);
-      self.ns_menu.addItem_(menu_item.ns_menu_item);
+      menu_item
+        .ns_menu_item
+        .as_ptr()
+        .setSubmenu_(submenu.ns_menu.as_ptr());
+      self
+        .ns_menu
+        .as_ptr()
+        .addItem_(menu_item.ns_menu_item.as_ptr());
+    }
+    Ok(())
+  }
+  /// Trigger the custom item with this id, searching submenus too. See `MenuItem::activate`.
+  ///
+  /// Returns false if no enabled item with this id was found.
+  pub fn perform_action_by_id(&self, menu_id: MenuId) -> bool {
+    unsafe {
+      find_menu_item(self.ns_menu.as_ptr(), menu_id)
+        .map(|item| activate_menu_item(item))
+        .unwrap_or(false)
     }
   }
   pub fn set_title(&self, title: &str) {
     unsafe {
       let menu_title = NSString::alloc(nil).init_str(title);
-      let () = msg_send![self.ns_menu, setTitle: menu_title];
+      let () = msg_send![self.ns_menu.as_ptr(), setTitle: menu_title];
+    }
+  }
+  /// Let the validation handler decide the state of custom items right before the menu is displayed.
+  /// Disabled by default, in which case items keep the state set through `MenuItem`.
+  ///
+  /// Only applies to this menu, turn it on for each submenu as well.
+  /// See `validation::set_validation_handler`.
+  pub fn set_auto_validation(&self, auto_validation: bool) {
+    unsafe {
+      let status = match auto_validation {
+        true => YES,
+        false => NO,
+      };
+      let () = msg_send![self.ns_menu.as_ptr(), setAutoenablesItems: status];
     }
   }
 }
@@ -147,3 +366,14 @@ impl Default for ContextMenu {
     Self::new()
   }
 }
+
+fn make_ns_menu() -> Result<Retained, MenuError> {
+  if !is_main_thread() {
+    return Err(MenuError::NotMainThread);
+  }
+  unsafe {
+    let ns_menu = Retained::from_owned(NSMenu::new(nil));
+    let () = msg_send![ns_menu.as_ptr(), setAutoenablesItems: NO];
+    Ok(ns_menu)
+  }
+}
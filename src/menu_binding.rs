@@ -0,0 +1,323 @@
+#[cfg(target_os = "macos")]
+use crate::{menu_handle::MenuItemHandle, platform_impl::menu_item::MenuItem};
+use std::{
+  fmt,
+  sync::{Arc, Mutex, MutexGuard, PoisonError},
+  thread,
+};
+
+type Observer<T> = Arc<dyn Fn(&T) + Send + Sync>;
+
+struct Binding<T> {
+  // Key of the bound item, None for `MenuBinding::observe`
+  #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+  item: Option<usize>,
+  observer: Observer<T>,
+}
+
+struct Inner<T> {
+  value: T,
+  bindings: Vec<Binding<T>>,
+  // Bumped by every change, tells the observers a newer value is pending
+  generation: u64,
+  // Whether a thread is calling the observers
+  notifying: bool,
+}
+
+/// Value shared with the state of menu items, so changing it updates them.
+///
+/// Clones share the same value. Setting it works from any thread, the items are updated on the
+/// main thread by `menu_handle::process_commands`. Observers are called without holding the
+/// value, so they can read or set it again.
+///
+/// Observers are called by one thread at a time, in the order of the changes. A change made
+/// while they are called, by an observer or another thread, is passed to them by the thread
+/// already calling them, which skips the stale value for the remaining observers:
+///
+/// ```ignore
+/// let show_sidebar = MenuBinding::new(false);
+/// show_sidebar.bind_selected(&sidebar_item, |shown| *shown);
+/// show_sidebar.bind_title(&sidebar_item, |shown| match shown {
+///   true => "Hide Sidebar".to_string(),
+///   false => "Show Sidebar".to_string(),
+/// });
+///
+/// show_sidebar.set(true);
+/// ```
+pub struct MenuBinding<T> {
+  inner: Arc<Mutex<Inner<T>>>,
+}
+
+impl<T: Clone + PartialEq + Send + 'static> MenuBinding<T> {
+  pub fn new(value: T) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(Inner {
+        value,
+        bindings: Vec::new(),
+        generation: 0,
+        notifying: false,
+      })),
+    }
+  }
+  pub fn get(&self) -> T {
+    self.lock().value.clone()
+  }
+  /// Change the value and update the bound items, does nothing if the value is the same.
+  pub fn set(&self, value: T) {
+    self.update(|current| *current = value);
+  }
+  /// Change the value in place, see `set`.
+  ///
+  /// `update` runs while holding the value, so concurrent updates are never lost, and must not
+  /// use the binding.
+  pub fn update<F: FnOnce(&mut T)>(&self, update: F) {
+    {
+      let mut inner = self.lock();
+      let previous = inner.value.clone();
+      update(&mut inner.value);
+      if inner.value == previous {
+        return;
+      }
+      inner.generation += 1;
+      if inner.notifying {
+        return;
+      }
+      inner.notifying = true;
+    }
+    self.notify();
+  }
+
+  // Call the observers until they have seen the latest value
+  fn notify(&self) {
+    let _reset = ResetOnUnwind(&self.inner);
+    'changes: loop {
+      let (generation, value, observers) = {
+        let inner = self.lock();
+        let observers: Vec<Observer<T>> = inner
+          .bindings
+          .iter()
+          .map(|binding| binding.observer.clone())
+          .collect();
+        (inner.generation, inner.value.clone(), observers)
+      };
+      for observer in observers {
+        observer(&value);
+        if self.lock().generation != generation {
+          continue 'changes;
+        }
+      }
+      let mut inner = self.lock();
+      if inner.generation == generation {
+        inner.notifying = false;
+        return;
+      }
+    }
+  }
+
+  fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+    self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+  /// Enable the item when `map` returns true.
+  #[cfg(target_os = "macos")]
+  pub fn bind_enabled<F>(&self, item: &MenuItem, map: F)
+  where
+    F: Fn(&T) -> bool + Send + Sync + 'static,
+  {
+    self.bind(item, move |item, value| item.set_enabled(map(value)));
+  }
+  /// Check the item when `map` returns true.
+  #[cfg(target_os = "macos")]
+  pub fn bind_selected<F>(&self, item: &MenuItem, map: F)
+  where
+    F: Fn(&T) -> bool + Send + Sync + 'static,
+  {
+    self.bind(item, move |item, value| item.set_selected(map(value)));
+  }
+  /// Set the title of the item to what `map` returns.
+  #[cfg(target_os = "macos")]
+  pub fn bind_title<F>(&self, item: &MenuItem, map: F)
+  where
+    F: Fn(&T) -> String + Send + Sync + 'static,
+  {
+    self.bind(item, move |item, value| item.set_title(&map(value)));
+  }
+  /// Stop updating the item. The bindings keep their items alive until then.
+  #[cfg(target_os = "macos")]
+  pub fn unbind(&self, item: &MenuItem) {
+    let key = unsafe { item.as_raw() } as usize;
+    self
+      .lock()
+      .bindings
+      .retain(|binding| binding.item != Some(key));
+  }
+  /// Call `observer` with the current value, then with every new value.
+  pub fn observe<F>(&self, observer: F)
+  where
+    F: Fn(&T) + Send + Sync + 'static,
+  {
+    self.add_binding(None, Arc::new(observer));
+  }
+
+  #[cfg(target_os = "macos")]
+  fn bind<F>(&self, item: &MenuItem, apply: F)
+  where
+    F: Fn(&MenuItemHandle, &T) + Send + Sync + 'static,
+  {
+    // The observer keeps the item alive
+    let item = item.handle();
+    let key = item.key();
+    self.add_binding(Some(key), Arc::new(move |value: &T| apply(&item, value)));
+  }
+
+  // Apply the current value right away, so the item doesn't show a stale state
+  fn add_binding(&self, item: Option<usize>, observer: Observer<T>) {
+    let value = {
+      let mut inner = self.lock();
+      inner.bindings.push(Binding {
+        item,
+        observer: observer.clone(),
+      });
+      inner.value.clone()
+    };
+    observer(&value);
+  }
+}
+
+// Lets the next change call the observers when one of them panics
+struct ResetOnUnwind<'a, T>(&'a Mutex<Inner<T>>);
+
+impl<T> Drop for ResetOnUnwind<'_, T> {
+  fn drop(&mut self) {
+    if thread::panicking() {
+      self
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .notifying = false;
+    }
+  }
+}
+
+impl<T> Clone for MenuBinding<T> {
+  fn clone(&self) -> Self {
+    Self {
+      inner: self.inner.clone(),
+    }
+  }
+}
+
+impl<T: Default + Clone + PartialEq + Send + 'static> Default for MenuBinding<T> {
+  fn default() -> Self {
+    Self::new(T::default())
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MenuBinding<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+    f.debug_struct("MenuBinding")
+      .field("value", &inner.value)
+      .field("bindings", &inner.bindings.len())
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+  };
+
+  #[test]
+  fn observers_see_every_change() {
+    let binding = MenuBinding::new(1);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let observed = seen.clone();
+    binding.observe(move |value| observed.lock().unwrap().push(*value));
+    binding.set(2);
+    binding.set(2);
+    binding.update(|value| *value += 1);
+    binding.clone().set(4);
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(binding.get(), 4);
+  }
+
+  #[test]
+  fn observers_can_use_the_binding() {
+    let binding = MenuBinding::new(0);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let reentrant = binding.clone();
+    let counted = calls.clone();
+    // Rounds odd values up, which sets the binding from its own observer
+    binding.observe(move |value| {
+      counted.fetch_add(1, Ordering::SeqCst);
+      assert_eq!(reentrant.get(), *value);
+      if value % 2 == 1 {
+        reentrant.set(value + 1);
+      }
+    });
+    binding.set(3);
+    assert_eq!(binding.get(), 4);
+    // 0, 3, then 4
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert!(format!("{:?}", binding).contains("value: 4"));
+  }
+
+  #[test]
+  fn later_observers_skip_stale_values() {
+    let binding = MenuBinding::new(0);
+    let reentrant = binding.clone();
+    // Sets 2 whenever 1 is set
+    binding.observe(move |value| {
+      if *value == 1 {
+        reentrant.set(2);
+      }
+    });
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let observed = seen.clone();
+    binding.observe(move |value| observed.lock().unwrap().push(*value));
+    binding.set(1);
+    assert_eq!(binding.get(), 2);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 2]);
+  }
+
+  #[test]
+  fn concurrent_changes() {
+    let binding = MenuBinding::new(0);
+    let last_seen = Arc::new(AtomicUsize::new(0));
+    let observed = last_seen.clone();
+    binding.observe(move |value| observed.store(*value, Ordering::SeqCst));
+    let threads: Vec<_> = (0..8)
+      .map(|_| {
+        let binding = binding.clone();
+        thread::spawn(move || {
+          for _ in 0..100 {
+            binding.update(|value| *value += 1);
+          }
+        })
+      })
+      .collect();
+    for thread in threads {
+      thread.join().unwrap();
+    }
+    assert_eq!(binding.get(), 800);
+    // The last value reached the observer, whichever thread called it
+    assert_eq!(last_seen.load(Ordering::SeqCst), 800);
+  }
+
+  #[test]
+  fn panicking_observers_dont_stop_notifications() {
+    let binding = MenuBinding::new(0);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let observed = seen.clone();
+    binding.observe(move |value| {
+      assert_ne!(*value, 1);
+      observed.lock().unwrap().push(*value);
+    });
+    assert!(catch_unwind(AssertUnwindSafe(|| binding.set(1))).is_err());
+    binding.set(2);
+    assert_eq!(*seen.lock().unwrap(), vec![0, 2]);
+  }
+}
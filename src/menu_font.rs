@@ -0,0 +1,132 @@
+use crate::{
+  menu::{ContextMenu, Menu},
+  platform_impl::menu_item::{set_title_font, MenuItem},
+};
+use cocoa::{
+  base::{id, nil, NO, YES},
+  foundation::NSString,
+};
+use objc::{class, msg_send, sel, sel_impl};
+
+// NSBoldFontMask
+const BOLD_FONT_MASK: usize = 2;
+
+/// Font of a menu or an item, see `Menu::set_font`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontSpec {
+  family: Option<String>,
+  size: f64,
+  bold: bool,
+}
+
+impl FontSpec {
+  /// Menu font of the system at its default size.
+  pub fn new() -> Self {
+    Self {
+      family: None,
+      size: 0.,
+      bold: false,
+    }
+  }
+  /// Font name, such as `"Menlo"`. Default to the menu font of the system, which is also used
+  /// when no font has this name.
+  pub fn with_family(mut self, family: &str) -> Self {
+    self.family = Some(family.to_string());
+    self
+  }
+  /// Size in points, 0 for the default size of the system. Default to 0
+  pub fn with_size(mut self, size: f64) -> Self {
+    self.size = size;
+    self
+  }
+  /// Default to false
+  pub fn with_bold(mut self, bold: bool) -> Self {
+    self.bold = bold;
+    self
+  }
+
+  unsafe fn to_ns_font(&self) -> id {
+    let menu_font: id = msg_send![class!(NSFont), menuFontOfSize: self.size];
+    let mut font: id = match &self.family {
+      Some(family) => {
+        // Size 0 is the default size of other fonts, not of menus
+        let size: f64 = msg_send![menu_font, pointSize];
+        let name = NSString::alloc(nil).init_str(family);
+        msg_send![class!(NSFont), fontWithName: name size: size]
+      }
+      None => nil,
+    };
+    if font == nil {
+      font = menu_font;
+    }
+    if self.bold {
+      let font_manager: id = msg_send![class!(NSFontManager), sharedFontManager];
+      font = msg_send![font_manager, convertFont: font toHaveTrait: BOLD_FONT_MASK];
+    }
+    font
+  }
+}
+
+impl Default for FontSpec {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Menu {
+  /// Font of the items of this menu and its submenus, unless they set their own.
+  pub fn set_font(&self, font: &FontSpec) {
+    unsafe { set_menu_font(self.as_raw(), font) }
+  }
+  /// Width of the menu in points, it still grows to fit its items.
+  pub fn set_minimum_width(&self, width: f64) {
+    unsafe { set_menu_minimum_width(self.as_raw(), width) }
+  }
+  /// Whether to keep room for checkmarks in front of the titles. Default to true
+  pub fn set_shows_state_column(&self, shows_state_column: bool) {
+    unsafe { set_menu_shows_state_column(self.as_raw(), shows_state_column) }
+  }
+}
+
+impl ContextMenu {
+  /// See `Menu::set_font`.
+  pub fn set_font(&self, font: &FontSpec) {
+    unsafe { set_menu_font(self.as_raw(), font) }
+  }
+  /// See `Menu::set_minimum_width`.
+  pub fn set_minimum_width(&self, width: f64) {
+    unsafe { set_menu_minimum_width(self.as_raw(), width) }
+  }
+  /// See `Menu::set_shows_state_column`.
+  pub fn set_shows_state_column(&self, shows_state_column: bool) {
+    unsafe { set_menu_shows_state_column(self.as_raw(), shows_state_column) }
+  }
+}
+
+impl MenuItem {
+  /// Font of this item only, None to use the font of its menu again.
+  /// Kept when the title changes with `MenuItem::set_title`.
+  pub fn set_font(&self, font: Option<&FontSpec>) -> &Self {
+    unsafe {
+      let font = font.map_or(nil, |font| font.to_ns_font());
+      set_title_font(self.as_raw(), font);
+    }
+    self
+  }
+}
+
+unsafe fn set_menu_font(ns_menu: id, font: &FontSpec) {
+  let () = msg_send![ns_menu, setFont: font.to_ns_font()];
+}
+
+unsafe fn set_menu_minimum_width(ns_menu: id, width: f64) {
+  let () = msg_send![ns_menu, setMinimumWidth: width];
+}
+
+unsafe fn set_menu_shows_state_column(ns_menu: id, shows_state_column: bool) {
+  let status = match shows_state_column {
+    true => YES,
+    false => NO,
+  };
+  let () = msg_send![ns_menu, setShowsStateColumn: status];
+}
@@ -0,0 +1,297 @@
+use crate::{
+  accessibility::AccessibilityRole,
+  error::MenuError,
+  menu::Menu,
+  platform_impl::{
+    key::{KeyEquivalent, OwnedKeyEquivalent},
+    menu::is_main_thread,
+    menu_item::MenuItem,
+    menu_item_attributes::MenuItemAttributes,
+  },
+};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use objc::runtime::Sel;
+use once_cell::sync::OnceCell;
+use std::{
+  cell::RefCell,
+  collections::{hash_map::Entry, HashMap},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex, PoisonError,
+  },
+};
+
+type WakeUpHandler = Arc<dyn Fn() + Send + Sync>;
+
+enum Target {
+  Menu(Menu),
+  MenuItem(MenuItem),
+}
+
+#[derive(Debug)]
+struct OwnedMenuItemAttributes {
+  title: String,
+  selector: Option<Sel>,
+  key_equivalent: Option<OwnedKeyEquivalent>,
+  enabled: bool,
+  selected: bool,
+  accessibility_label: Option<String>,
+  accessibility_help: Option<String>,
+  accessibility_role: Option<AccessibilityRole>,
+}
+
+impl OwnedMenuItemAttributes {
+  fn new(attributes: MenuItemAttributes) -> Self {
+    Self {
+      title: attributes.title.to_string(),
+      selector: attributes.selector,
+      key_equivalent: attributes.key_equivalent.map(KeyEquivalent::into_owned),
+      enabled: attributes.enabled,
+      selected: attributes.selected,
+      accessibility_label: attributes.accessibility_label.map(str::to_string),
+      accessibility_help: attributes.accessibility_help.map(str::to_string),
+      accessibility_role: attributes.accessibility_role,
+    }
+  }
+  fn as_attributes(&self) -> MenuItemAttributes<'_> {
+    MenuItemAttributes {
+      title: &self.title,
+      selector: self.selector,
+      key_equivalent: self
+        .key_equivalent
+        .as_ref()
+        .map(OwnedKeyEquivalent::as_key_equivalent),
+      enabled: self.enabled,
+      selected: self.selected,
+      accessibility_label: self.accessibility_label.as_deref(),
+      accessibility_help: self.accessibility_help.as_deref(),
+      accessibility_role: self.accessibility_role,
+    }
+  }
+}
+
+#[derive(Debug)]
+enum Command {
+  SetMenuTitle(usize, String),
+  InsertItem(usize, usize, OwnedMenuItemAttributes, usize),
+  RemoveItem(usize, usize),
+  SetItemTitle(usize, String),
+  SetItemEnabled(usize, bool),
+  SetItemSelected(usize, bool),
+  Release(usize),
+}
+
+thread_local! {
+  // Menus and items with a live handle, only ever touched on the main thread.
+  static TARGETS: RefCell<HashMap<usize, (Target, usize)>> = RefCell::new(HashMap::new());
+}
+
+fn get_command_channel() -> &'static (Sender<Command>, Receiver<Command>) {
+  static CHANNEL: OnceCell<(Sender<Command>, Receiver<Command>)> = OnceCell::new();
+  CHANNEL.get_or_init(unbounded::<Command>)
+}
+
+fn get_wake_up_handler() -> &'static Mutex<Option<WakeUpHandler>> {
+  static HANDLER: OnceCell<Mutex<Option<WakeUpHandler>>> = OnceCell::new();
+  HANDLER.get_or_init(|| Mutex::new(None))
+}
+
+/// Set a function called whenever a handle queues an operation, so the event loop can be woken up
+/// to call `process_commands`. Such as sending a user event through an `EventLoopProxy`.
+pub fn set_wake_up_handler<F: Fn() + Send + 'static>(handler: F) {
+  // `EventLoopProxy` is `Send` but not `Sync`
+  let handler = Mutex::new(handler);
+  *get_wake_up_handler()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(move || {
+    (handler.lock().unwrap_or_else(PoisonError::into_inner))()
+  }));
+}
+
+fn send_command(command: Command) {
+  // Only fails if the receiver is gone, which never happens with a static channel
+  let _ = get_command_channel().0.send(command);
+  // Not called under the lock, so the handler may replace itself
+  let handler = get_wake_up_handler()
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .clone();
+  if let Some(handler) = handler {
+    handler();
+  }
+}
+
+// Key of an item that is inserted by `process_commands`. Objective-C objects are aligned, so odd
+// keys never collide with the address of a registered target.
+fn next_pending_key() -> usize {
+  static NEXT_KEY: AtomicUsize = AtomicUsize::new(1);
+  NEXT_KEY.fetch_add(2, Ordering::Relaxed)
+}
+
+fn register(key: usize, target: Target) -> Arc<HandleKey> {
+  TARGETS.with(|targets| match targets.borrow_mut().entry(key) {
+    Entry::Occupied(mut entry) => entry.get_mut().1 += 1,
+    Entry::Vacant(entry) => {
+      entry.insert((target, 1));
+    }
+  });
+  Arc::new(HandleKey(key))
+}
+
+/// Apply the operations queued by `MenuHandle` and `MenuItemHandle`.
+///
+/// Must be called on the main thread, typically from the event loop. Returns
+/// `MenuError::NotMainThread` without applying anything on other threads:
+///
+/// ```ignore
+/// event_loop.run(move |event, _, control_flow| {
+///   winit_menu_macos::menu_handle::process_commands().unwrap();
+///   // ...
+/// });
+/// ```
+pub fn process_commands() -> Result<(), MenuError> {
+  if !is_main_thread() {
+    return Err(MenuError::NotMainThread);
+  }
+  let rx = &get_command_channel().1;
+  TARGETS.with(|targets| {
+    while let Ok(command) = rx.try_recv() {
+      let mut targets = targets.borrow_mut();
+      match command {
+        Command::SetMenuTitle(key, title) => {
+          if let Some((Target::Menu(menu), _)) = targets.get(&key) {
+            menu.set_title(&title);
+          }
+        }
+        Command::InsertItem(key, index, attributes, item_key) => {
+          let item = match targets.get(&key) {
+            // There is no way to report the error back to the thread of the handle, later
+            // operations on the item handle are ignored
+            Some((Target::Menu(menu), _)) => {
+              menu.insert_item(index, attributes.as_attributes()).ok()
+            }
+            _ => None,
+          };
+          if let Some(item) = item {
+            // Released by the `HandleKey` of the returned `MenuItemHandle`
+            targets.insert(item_key, (Target::MenuItem(item), 1));
+          }
+        }
+        Command::RemoveItem(key, item_key) => {
+          if let (Some((Target::Menu(menu), _)), Some((Target::MenuItem(item), _))) =
+            (targets.get(&key), targets.get(&item_key))
+          {
+            menu.remove_item(item);
+          }
+        }
+        Command::SetItemTitle(key, title) => {
+          if let Some((Target::MenuItem(item), _)) = targets.get(&key) {
+            item.set_title(&title);
+          }
+        }
+        Command::SetItemEnabled(key, enabled) => {
+          if let Some((Target::MenuItem(item), _)) = targets.get(&key) {
+            item.set_enabled(enabled);
+          }
+        }
+        Command::SetItemSelected(key, selected) => {
+          if let Some((Target::MenuItem(item), _)) = targets.get(&key) {
+            item.set_selected(selected);
+          }
+        }
+        Command::Release(key) => {
+          if let Entry::Occupied(mut entry) = targets.entry(key) {
+            entry.get_mut().1 -= 1;
+            if entry.get().1 == 0 {
+              entry.remove();
+            }
+          }
+        }
+      }
+    }
+  });
+  Ok(())
+}
+
+// Key of a registered target, released on the main thread once the last handle is dropped.
+#[derive(Debug)]
+struct HandleKey(usize);
+
+impl Drop for HandleKey {
+  fn drop(&mut self) {
+    send_command(Command::Release(self.0));
+  }
+}
+
+/// `Send` and `Sync` reference to a `Menu`, see `Menu::handle`.
+///
+/// Operations are queued and applied on the main thread by `process_commands`.
+#[derive(Debug, Clone)]
+pub struct MenuHandle {
+  key: Arc<HandleKey>,
+}
+
+impl MenuHandle {
+  pub fn set_title(&self, title: &str) {
+    send_command(Command::SetMenuTitle(self.key.0, title.to_string()));
+  }
+  /// See `Menu::insert_item`. The returned handle can be used right away, its operations are
+  /// applied after the insertion.
+  pub fn insert_item(&self, index: usize, attributes: MenuItemAttributes) -> MenuItemHandle {
+    let key = Arc::new(HandleKey(next_pending_key()));
+    send_command(Command::InsertItem(
+      self.key.0,
+      index,
+      OwnedMenuItemAttributes::new(attributes),
+      key.0,
+    ));
+    MenuItemHandle { key }
+  }
+  /// See `Menu::remove_item`.
+  pub fn remove_item(&self, item: &MenuItemHandle) {
+    send_command(Command::RemoveItem(self.key.0, item.key.0));
+  }
+}
+
+/// `Send` and `Sync` reference to a `MenuItem`, see `MenuItem::handle`.
+///
+/// Operations are queued and applied on the main thread by `process_commands`.
+#[derive(Debug, Clone)]
+pub struct MenuItemHandle {
+  key: Arc<HandleKey>,
+}
+
+impl MenuItemHandle {
+  pub fn set_title(&self, title: &str) {
+    send_command(Command::SetItemTitle(self.key.0, title.to_string()));
+  }
+  pub fn set_enabled(&self, is_enabled: bool) {
+    send_command(Command::SetItemEnabled(self.key.0, is_enabled));
+  }
+  pub fn set_selected(&self, is_selected: bool) {
+    send_command(Command::SetItemSelected(self.key.0, is_selected));
+  }
+  pub(crate) fn key(&self) -> usize {
+    self.key.0
+  }
+}
+
+impl Menu {
+  /// Get a handle to update this menu from any thread.
+  pub fn handle(&self) -> MenuHandle {
+    let key = unsafe { self.as_raw() } as usize;
+    MenuHandle {
+      key: register(key, Target::Menu(self.clone())),
+    }
+  }
+}
+
+impl MenuItem {
+  /// Get a handle to update this item from any thread.
+  pub fn handle(&self) -> MenuItemHandle {
+    let key = unsafe { self.as_raw() } as usize;
+    MenuItemHandle {
+      key: register(key, Target::MenuItem(self.clone())),
+    }
+  }
+}
@@ -0,0 +1,251 @@
+use crate::platform_impl::{key::OwnedKeyEquivalent, menu::MenuId};
+#[cfg(target_os = "macos")]
+use crate::{menu::Menu, platform_impl::menu_item::MenuItem};
+use std::cmp::Reverse;
+
+/// Command listed by a `MenuIndex`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuIndexEntry {
+  /// Titles of the submenus leading to the item, ending with the title of the item.
+  pub path: Vec<String>,
+  /// `MenuId::EMPTY` for native items.
+  pub menu_id: MenuId,
+  pub key_equivalent: Option<OwnedKeyEquivalent>,
+  pub enabled: bool,
+  pub selected: bool,
+}
+
+impl MenuIndexEntry {
+  pub fn title(&self) -> &str {
+    self.path.last().map_or("", String::as_str)
+  }
+}
+
+/// Entry matching a search, see `MenuIndex::search`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchMatch<'a> {
+  /// Position of the entry in `MenuIndex::entries`, pass it to `MenuIndex::activate`.
+  pub index: usize,
+  pub entry: &'a MenuIndexEntry,
+  /// Higher is better.
+  pub score: i64,
+}
+
+/// Flat, searchable list of every command of a menu tree, such as for a command palette.
+///
+/// The index is a snapshot, build a new one after changing the menu.
+#[derive(Debug, Clone, Default)]
+pub struct MenuIndex {
+  entries: Vec<MenuIndexEntry>,
+  // Same order as the entries, empty if built with `from_entries`
+  #[cfg(target_os = "macos")]
+  items: Vec<MenuItem>,
+}
+
+impl MenuIndex {
+  /// Index every item of the menu and its submenus, skipping separators and submenu items.
+  #[cfg(target_os = "macos")]
+  pub fn new(menu: &Menu) -> Self {
+    let mut index = Self::default();
+    index.add_menu(menu, &mut Vec::new());
+    index
+  }
+  /// Build an index from entries only, which can be searched but not activated.
+  pub fn from_entries(entries: Vec<MenuIndexEntry>) -> Self {
+    Self {
+      entries,
+      #[cfg(target_os = "macos")]
+      items: Vec::new(),
+    }
+  }
+  #[cfg(target_os = "macos")]
+  fn add_menu(&mut self, menu: &Menu, path: &mut Vec<String>) {
+    for item in menu.items() {
+      if item.is_separator() {
+        continue;
+      }
+      path.push(item.title());
+      match item.submenu() {
+        Some(submenu) => self.add_menu(&submenu, path),
+        None => {
+          self.entries.push(MenuIndexEntry {
+            path: path.clone(),
+            menu_id: item.id(),
+            key_equivalent: item.key_equivalent(),
+            enabled: item.is_enabled(),
+            selected: item.is_selected(),
+          });
+          self.items.push(item);
+        }
+      }
+      path.pop();
+    }
+  }
+  pub fn entries(&self) -> &[MenuIndexEntry] {
+    &self.entries
+  }
+  /// Entries fuzzy matching the query, best first.
+  ///
+  /// Matches in the title rank above matches in the rest of the path, and enabled entries
+  /// above disabled ones with the same score. An empty query returns every entry in menu order.
+  pub fn search(&self, query: &str) -> Vec<SearchMatch<'_>> {
+    let mut matches: Vec<SearchMatch> = self
+      .entries
+      .iter()
+      .enumerate()
+      .filter_map(|(index, entry)| {
+        Some(SearchMatch {
+          index,
+          entry,
+          score: entry_score(query, entry)?,
+        })
+      })
+      .collect();
+    // Stable sort, so ties keep the menu order
+    matches.sort_by_key(|m| (Reverse(m.score), !m.entry.enabled));
+    matches
+  }
+  /// Activate the entry at this index through the same path as a click, see `MenuItem::activate`.
+  ///
+  /// Returns false if the index is out of range, the index was built with `from_entries`,
+  /// or the item could not be activated.
+  #[cfg(target_os = "macos")]
+  pub fn activate(&self, index: usize) -> bool {
+    match self.items.get(index) {
+      Some(item) => item.activate(),
+      None => false,
+    }
+  }
+}
+
+// Bonus for matching the title rather than the submenus leading to it
+const TITLE_BONUS: i64 = 10;
+// Bonus for matching every character of the candidate
+const EXACT_BONUS: i64 = 10;
+
+fn entry_score(query: &str, entry: &MenuIndexEntry) -> Option<i64> {
+  if query.trim().is_empty() {
+    return Some(0);
+  }
+  let title_score = fuzzy_score(query, entry.title()).map(|score| score + TITLE_BONUS);
+  let path_score = fuzzy_score(query, &entry.path.join(" "));
+  title_score.max(path_score)
+}
+
+/// Score how well `query` matches `candidate`, None if the characters of the query,
+/// whitespace aside, don't all appear in order in the candidate. Case insensitive.
+///
+/// Consecutive characters and characters starting a word score higher, gaps score lower,
+/// and matching the whole candidate scores highest.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+  let candidate: Vec<char> = candidate.chars().collect();
+  let mut score = 0;
+  let mut position = 0;
+  let mut first = true;
+  let mut matched = 0;
+  for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+    let gap = candidate[position..]
+      .iter()
+      .position(|c| eq_ignore_case(*c, query_char))?;
+    let index = position + gap;
+    score += 1;
+    if gap == 0 && !first {
+      score += 5;
+    }
+    if index == 0
+      || !candidate[index - 1].is_alphanumeric()
+      || (candidate[index - 1].is_lowercase() && candidate[index].is_uppercase())
+    {
+      score += 3;
+    }
+    score -= gap.min(3) as i64;
+    first = false;
+    matched += 1;
+    position = index + 1;
+  }
+  if matched > 0 && matched == candidate.iter().filter(|c| !c.is_whitespace()).count() {
+    score += EXACT_BONUS;
+  }
+  Some(score)
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+  a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(path: &[&str], enabled: bool) -> MenuIndexEntry {
+    MenuIndexEntry {
+      path: path.iter().map(|title| title.to_string()).collect(),
+      menu_id: MenuId::new(path.last().unwrap()),
+      key_equivalent: None,
+      enabled,
+      selected: false,
+    }
+  }
+
+  fn titles<'a>(matches: &[SearchMatch<'a>]) -> Vec<&'a str> {
+    matches.iter().map(|m| m.entry.title()).collect()
+  }
+
+  #[test]
+  fn exact_before_prefix_before_subsequence() {
+    let exact = fuzzy_score("copy", "Copy").unwrap();
+    let prefix = fuzzy_score("copy", "Copy Link").unwrap();
+    let word_starts = fuzzy_score("cl", "Copy Link").unwrap();
+    let subsequence = fuzzy_score("copy", "Clone Policy").unwrap();
+    assert!(exact > prefix, "{} {}", exact, prefix);
+    assert!(prefix > subsequence, "{} {}", prefix, subsequence);
+    assert!(word_starts > fuzzy_score("cl", "Cancel").unwrap());
+  }
+
+  #[test]
+  fn case_and_whitespace_insensitive() {
+    assert_eq!(fuzzy_score("COPY", "copy"), fuzzy_score("copy", "Copy"));
+    assert_eq!(fuzzy_score("Copy", "COPY"), fuzzy_score("copy", "copy"));
+    assert_eq!(
+      fuzzy_score("copy link", "Copy Link"),
+      fuzzy_score("copylink", "Copy Link")
+    );
+    assert_eq!(fuzzy_score("É", "énergie"), fuzzy_score("é", "énergie"));
+  }
+
+  #[test]
+  fn non_matches() {
+    assert_eq!(fuzzy_score("xyz", "Copy"), None);
+    assert_eq!(fuzzy_score("ypoc", "Copy"), None);
+    assert_eq!(fuzzy_score("copyy", "Copy"), None);
+    assert_eq!(fuzzy_score("a", ""), None);
+    assert_eq!(fuzzy_score("", "Copy"), Some(0));
+  }
+
+  #[test]
+  fn search_ranking() {
+    let index = MenuIndex::from_entries(vec![
+      entry(&["Edit", "Copy Link"], true),
+      entry(&["Edit", "Paste"], true),
+      entry(&["Copy", "As Text"], true),
+      entry(&["Edit", "Copy"], false),
+      entry(&["File", "Copy"], true),
+    ]);
+    let matches = index.search("copy");
+    // Exact titles first, enabled ones before disabled ones, then prefixes, then submenu matches
+    assert_eq!(
+      titles(&matches),
+      vec!["Copy", "Copy", "Copy Link", "As Text"]
+    );
+    assert_eq!(matches[0].index, 4);
+    assert_eq!(matches[1].index, 3);
+    assert!(matches.windows(2).all(|m| m[0].score >= m[1].score));
+
+    assert_eq!(index.search("  ").len(), 5);
+    assert_eq!(titles(&index.search("")), titles(&index.search(" ")));
+    assert!(index.search("zzz").is_empty());
+    // Entries only, nothing to activate
+    #[cfg(target_os = "macos")]
+    assert!(!index.activate(0));
+  }
+}
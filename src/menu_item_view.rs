@@ -0,0 +1,240 @@
+use crate::{
+  error::MenuError,
+  menu::{ContextMenu, Menu},
+  platform_impl::{menu::MenuType, menu_item::MenuItem},
+};
+use cocoa::{
+  appkit::NSMenu,
+  base::{id, nil, BOOL, NO, YES},
+  foundation::{NSAutoreleasePool, NSPoint, NSRect, NSSize, NSString},
+};
+use objc::{class, msg_send, runtime::Class, sel, sel_impl};
+use std::path::PathBuf;
+
+// Size of the views, in points
+const VIEW_WIDTH: f64 = 220.;
+// Lines the controls up with the titles of the other items
+const MARGIN: f64 = 20.;
+const SPACING: f64 = 6.;
+
+/// Control shown in place of the title of a menu item, see `Menu::add_view_item`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuItemView {
+  /// Sends `Event::ViewValueChanged` with the new value while it is dragged.
+  /// `min <= value <= max`, all finite, or adding the item fails with `MenuError::InvalidSlider`.
+  Slider { min: f64, max: f64, value: f64 },
+  /// Progress from 0 to 1, None for an indeterminate bar.
+  Progress { value: Option<f64> },
+  /// Text with an optional image before it.
+  Label {
+    text: String,
+    image: Option<MenuImage>,
+  },
+}
+
+/// Image of a `MenuItemView::Label`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuImage {
+  /// Image of the app bundle or of the system, see `NSImage imageNamed:`.
+  Named(String),
+  /// SF Symbol, such as `"speaker.wave.2"`. Requires macOS 11, not shown on older versions.
+  SystemSymbol(String),
+  File(PathBuf),
+}
+
+impl Menu {
+  /// Add an item showing a control instead of its title.
+  ///
+  /// The title is still used for the id of the item and by menu searches, and screen readers
+  /// read it as the label of sliders and progress bars.
+  pub fn add_view_item(&self, title: &str, view: MenuItemView) -> Result<MenuItem, MenuError> {
+    let menu_item = make_view_item(title, &view, MenuType::MenuBar)?;
+    unsafe {
+      self.as_raw().addItem_(menu_item.as_raw());
+    }
+    Ok(menu_item)
+  }
+}
+
+impl ContextMenu {
+  /// See `Menu::add_view_item`.
+  pub fn add_view_item(&self, title: &str, view: MenuItemView) -> Result<MenuItem, MenuError> {
+    let menu_item = make_view_item(title, &view, MenuType::ContextMenu)?;
+    unsafe {
+      self.as_raw().addItem_(menu_item.as_raw());
+    }
+    Ok(menu_item)
+  }
+}
+
+impl MenuItem {
+  /// Update the value of a slider or progress view, None for an indeterminate progress.
+  /// Slider values are clamped to the bounds of the slider, NaN is ignored.
+  /// Does nothing for other items.
+  pub fn set_view_value(&self, value: Option<f64>) -> &Self {
+    unsafe {
+      if let Some(slider) = find_subview(self.as_raw(), class!(NSSlider)) {
+        if let Some(value) = value.filter(|value| !value.is_nan()) {
+          let min: f64 = msg_send![slider, minValue];
+          let max: f64 = msg_send![slider, maxValue];
+          let () = msg_send![slider, setDoubleValue: value.clamp(min, max)];
+        }
+      } else if let Some(bar) = find_subview(self.as_raw(), class!(NSProgressIndicator)) {
+        set_progress(bar, value);
+      }
+    }
+    self
+  }
+  /// Update the text of a label view. Does nothing for other items.
+  pub fn set_view_text(&self, text: &str) -> &Self {
+    unsafe {
+      if let Some(label) = find_subview(self.as_raw(), class!(NSTextField)) {
+        let text = NSString::alloc(nil).init_str(text);
+        let () = msg_send![label, setStringValue: text];
+      }
+    }
+    self
+  }
+}
+
+fn make_view_item(
+  title: &str,
+  view: &MenuItemView,
+  menu_type: MenuType,
+) -> Result<MenuItem, MenuError> {
+  if let MenuItemView::Slider { min, max, value } = *view {
+    let is_finite = [min, max, value].iter().all(|bound| bound.is_finite());
+    if !is_finite || !(min..=max).contains(&value) {
+      return Err(MenuError::InvalidSlider);
+    }
+  }
+  let menu_item = MenuItem::new(title, None, None, true, false, menu_type)?;
+  unsafe {
+    let item = menu_item.as_raw();
+    let container = match view {
+      MenuItemView::Slider { min, max, value } => {
+        // The item itself receives the changes, it knows its id
+        let slider: id = msg_send![
+          class!(NSSlider),
+          sliderWithValue: *value
+          minValue: *min
+          maxValue: *max
+          target: item
+          action: sel!(viewValueChanged:)
+        ];
+        let () = msg_send![slider, setContinuous: YES];
+        set_accessibility_label(slider, title);
+        make_container(&[slider], 28.)
+      }
+      MenuItemView::Progress { value } => {
+        let bar: id = msg_send![class!(NSProgressIndicator), alloc];
+        let bar: id =
+          msg_send![bar, initWithFrame: NSRect::new(NSPoint::new(0., 0.), NSSize::new(0., 0.))];
+        // NSProgressIndicatorStyleBar
+        let () = msg_send![bar, setStyle: 0_usize];
+        let () = msg_send![bar, setMinValue: 0_f64];
+        let () = msg_send![bar, setMaxValue: 1_f64];
+        set_progress(bar, *value);
+        set_accessibility_label(bar, title);
+        make_container(&[bar.autorelease()], 20.)
+      }
+      MenuItemView::Label { text, image } => {
+        let text = NSString::alloc(nil).init_str(text);
+        let label: id = msg_send![class!(NSTextField), labelWithString: text];
+        match image.as_ref().map(|image| make_image(image)) {
+          Some(image) if image != nil => {
+            let image_view: id = msg_send![class!(NSImageView), imageViewWithImage: image];
+            make_container(&[image_view, label], 22.)
+          }
+          _ => make_container(&[label], 22.),
+        }
+      }
+    };
+    let () = msg_send![item, setView: container];
+  }
+  Ok(menu_item)
+}
+
+// Lay the controls out left to right, every control but the last one being square
+unsafe fn make_container(controls: &[id], height: f64) -> id {
+  let container: id = msg_send![class!(NSView), alloc];
+  let frame = NSRect::new(NSPoint::new(0., 0.), NSSize::new(VIEW_WIDTH, height));
+  let container: id = msg_send![container, initWithFrame: frame];
+  let control_height = height - 4.;
+  let mut x = MARGIN;
+  for (index, control) in controls.iter().enumerate() {
+    let width = match index + 1 == controls.len() {
+      true => VIEW_WIDTH - MARGIN - x,
+      false => control_height,
+    };
+    let frame = NSRect::new(NSPoint::new(x, 2.), NSSize::new(width, control_height));
+    let () = msg_send![*control, setFrame: frame];
+    let () = msg_send![container, addSubview: *control];
+    x += width + SPACING;
+  }
+  container.autorelease()
+}
+
+unsafe fn make_image(image: &MenuImage) -> id {
+  match image {
+    MenuImage::Named(name) => {
+      let name = NSString::alloc(nil).init_str(name);
+      msg_send![class!(NSImage), imageNamed: name]
+    }
+    MenuImage::SystemSymbol(name) => {
+      let responds: BOOL = msg_send![
+        class!(NSImage),
+        respondsToSelector: sel!(imageWithSystemSymbolName:accessibilityDescription:)
+      ];
+      if responds == NO {
+        return nil;
+      }
+      let name = NSString::alloc(nil).init_str(name);
+      msg_send![class!(NSImage), imageWithSystemSymbolName: name accessibilityDescription: nil]
+    }
+    MenuImage::File(path) => {
+      let path = NSString::alloc(nil).init_str(&path.to_string_lossy());
+      let image: id = msg_send![class!(NSImage), alloc];
+      let image: id = msg_send![image, initWithContentsOfFile: path];
+      match image == nil {
+        true => nil,
+        false => image.autorelease(),
+      }
+    }
+  }
+}
+
+// The item itself isn't read once it shows a view
+unsafe fn set_accessibility_label(control: id, title: &str) {
+  let title = NSString::alloc(nil).init_str(title);
+  let () = msg_send![control, setAccessibilityLabel: title];
+}
+
+unsafe fn set_progress(bar: id, value: Option<f64>) {
+  match value {
+    Some(value) => {
+      let () = msg_send![bar, stopAnimation: nil];
+      let () = msg_send![bar, setIndeterminate: NO];
+      let () = msg_send![bar, setDoubleValue: value];
+    }
+    None => {
+      let () = msg_send![bar, setIndeterminate: YES];
+      let () = msg_send![bar, startAnimation: nil];
+    }
+  }
+}
+
+unsafe fn find_subview(item: id, class: &Class) -> Option<id> {
+  let view: id = msg_send![item, view];
+  if view == nil {
+    return None;
+  }
+  let subviews: id = msg_send![view, subviews];
+  let count: usize = msg_send![subviews, count];
+  (0..count)
+    .map(|index| -> id { msg_send![subviews, objectAtIndex: index] })
+    .find(|subview| {
+      let is_kind: BOOL = msg_send![*subview, isKindOfClass: class];
+      is_kind != NO
+    })
+}
@@ -0,0 +1,566 @@
+#[cfg(any(test, target_os = "macos"))]
+use crate::localization::localized_title;
+use crate::platform_impl::{
+  key::{KeyEquivalent, OwnedKeyEquivalent},
+  menu::MenuId,
+  native_menu_item_type::NativeMenuItemType,
+};
+#[cfg(target_os = "macos")]
+use crate::{
+  error::MenuError,
+  menu::Menu,
+  platform_impl::{
+    menu::MenuType, menu_item::MenuItem, native_menu_item_type::native_menu_item_type_of,
+  },
+};
+#[cfg(target_os = "macos")]
+use cocoa::base::id;
+#[cfg(target_os = "macos")]
+use objc::{msg_send, sel, sel_impl};
+#[cfg(any(test, target_os = "macos"))]
+use std::collections::hash_map::Entry;
+use std::{collections::HashMap, hash::Hash};
+
+/// Description of the items of a menu, applied with `Menu::reconcile`.
+#[derive(Debug, Clone, Default)]
+pub struct MenuSpec {
+  pub items: Vec<ItemSpec>,
+}
+
+impl MenuSpec {
+  pub fn new() -> Self {
+    Self::default()
+  }
+  pub fn with_item(mut self, item: ItemSpec) -> Self {
+    self.items.push(item);
+    self
+  }
+}
+
+/// Description of a menu item, see `MenuSpec`.
+#[derive(Debug, Clone)]
+pub enum ItemSpec {
+  /// Custom item, matched with the existing items by id.
+  Item(CustomItemSpec),
+  /// Predefined item, matched with the existing items by title.
+  Native(NativeMenuItemType),
+  /// Submenu, matched with the existing submenus by title.
+  Submenu { title: String, menu: MenuSpec },
+}
+
+/// Description of a custom item, see `ItemSpec::Item`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomItemSpec {
+  /// Default to `MenuId::new(title)`
+  pub id: MenuId,
+  pub title: String,
+  pub key_equivalent: Option<OwnedKeyEquivalent>,
+  /// Default to true
+  pub enabled: bool,
+  /// Default to false
+  pub selected: bool,
+}
+
+impl CustomItemSpec {
+  pub fn new(title: &str) -> Self {
+    Self {
+      id: MenuId::new(title),
+      title: title.to_string(),
+      key_equivalent: None,
+      enabled: true,
+      selected: false,
+    }
+  }
+  /// Keep the same id when the title changes, so the item is updated rather than replaced.
+  pub fn with_id(mut self, id: MenuId) -> Self {
+    self.id = id;
+    self
+  }
+  pub fn with_key_equivalent(mut self, key_equivalent: KeyEquivalent) -> Self {
+    self.key_equivalent = Some(key_equivalent.into_owned());
+    self
+  }
+  pub fn with_enabled(mut self, enabled: bool) -> Self {
+    self.enabled = enabled;
+    self
+  }
+  pub fn with_selected(mut self, selected: bool) -> Self {
+    self.selected = selected;
+    self
+  }
+}
+
+/// Operation turning the old list of keys into the new one, see `diff`.
+///
+/// Apply them in order. `old` is the index in the old list, which is also the current index
+/// of the item when the operation runs. `new` is the index in the new list, which is also
+/// where the item ends up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DiffOp {
+  /// Remove the item for good.
+  Remove { old: usize },
+  /// Take the item out, a later `Move` puts it back.
+  Detach { old: usize },
+  /// Put back a detached item.
+  Move { old: usize, new: usize },
+  /// Create an item.
+  Insert { new: usize },
+  /// The item stays, update it in place.
+  Update { old: usize, new: usize },
+}
+
+/// Minimal operations turning `old` into `new`, keys are expected to be unique in each list.
+///
+/// Items are removed and detached first, from the last to the first, then inserted, moved back
+/// or updated from the first to the last. Only the items outside the longest run of items
+/// already in the right order are moved.
+pub fn diff<K: Eq + Hash>(old: &[K], new: &[K]) -> Vec<DiffOp> {
+  let new_indices: HashMap<&K, usize> = new.iter().enumerate().map(|(i, key)| (key, i)).collect();
+  // New index of each old item, None if it goes away
+  let targets: Vec<Option<usize>> = old
+    .iter()
+    .map(|key| new_indices.get(key).copied())
+    .collect();
+  let kept: Vec<(usize, usize)> = targets
+    .iter()
+    .enumerate()
+    .filter_map(|(old, new)| Some((old, (*new)?)))
+    .collect();
+  let stable = longest_increasing_run(&kept.iter().map(|(_, new)| *new).collect::<Vec<_>>());
+  let mut stable_olds = vec![false; old.len()];
+  for index in stable {
+    stable_olds[kept[index].0] = true;
+  }
+
+  let mut ops = Vec::new();
+  for (old, target) in targets.iter().enumerate().rev() {
+    match target {
+      None => ops.push(DiffOp::Remove { old }),
+      Some(_) if !stable_olds[old] => ops.push(DiffOp::Detach { old }),
+      Some(_) => (),
+    }
+  }
+  let mut old_indices = vec![None; new.len()];
+  for (old, new) in kept {
+    old_indices[new] = Some(old);
+  }
+  for (new, old) in old_indices.into_iter().enumerate() {
+    ops.push(match old {
+      None => DiffOp::Insert { new },
+      Some(old) if stable_olds[old] => DiffOp::Update { old, new },
+      Some(old) => DiffOp::Move { old, new },
+    });
+  }
+  ops
+}
+
+// Positions in `values` of a longest strictly increasing subsequence
+fn longest_increasing_run(values: &[usize]) -> Vec<usize> {
+  // tails[len] is the position of the smallest last value of a run of length len + 1
+  let mut tails: Vec<usize> = Vec::new();
+  let mut previous: Vec<Option<usize>> = vec![None; values.len()];
+  for (position, value) in values.iter().enumerate() {
+    let len = tails.partition_point(|tail| values[*tail] < *value);
+    if len > 0 {
+      previous[position] = Some(tails[len - 1]);
+    }
+    match tails.get_mut(len) {
+      Some(tail) => *tail = position,
+      None => tails.push(position),
+    }
+  }
+  let mut run = Vec::with_capacity(tails.len());
+  let mut position = tails.last().copied();
+  while let Some(current) = position {
+    run.push(current);
+    position = previous[current];
+  }
+  run.reverse();
+  run
+}
+
+#[cfg(any(test, target_os = "macos"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ItemKey {
+  Id(MenuId),
+  Submenu(String),
+  Native(String),
+  Separator,
+}
+
+// Number the repeated keys, such as separators, so every key is unique
+#[cfg(any(test, target_os = "macos"))]
+fn unique_keys(keys: impl Iterator<Item = ItemKey>) -> Vec<(ItemKey, usize)> {
+  let mut counts: HashMap<ItemKey, usize> = HashMap::new();
+  keys
+    .map(|key| {
+      let count = match counts.entry(key.clone()) {
+        Entry::Occupied(mut entry) => {
+          *entry.get_mut() += 1;
+          *entry.get()
+        }
+        Entry::Vacant(entry) => *entry.insert(0),
+      };
+      (key, count)
+    })
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn item_key(item: &MenuItem) -> ItemKey {
+  // Before the submenu check, Services is a native item holding a submenu
+  match unsafe { native_menu_item_type_of(item.as_raw()) } {
+    Some(NativeMenuItemType::Separator) => ItemKey::Separator,
+    Some(_) if item.id().is_empty() => ItemKey::Native(item.title()),
+    _ if item.submenu().is_some() => ItemKey::Submenu(item.title()),
+    _ if !item.id().is_empty() => ItemKey::Id(item.id()),
+    _ => ItemKey::Native(item.title()),
+  }
+}
+
+#[cfg(any(test, target_os = "macos"))]
+fn spec_key(spec: &ItemSpec) -> ItemKey {
+  match spec {
+    ItemSpec::Item(item) => ItemKey::Id(item.id),
+    ItemSpec::Submenu { title, .. } => ItemKey::Submenu(title.clone()),
+    ItemSpec::Native(NativeMenuItemType::Separator) => ItemKey::Separator,
+    // Sends menu events, so it holds an id like custom items
+    ItemSpec::Native(item @ NativeMenuItemType::Preferences) => {
+      ItemKey::Id(MenuId::new(&localized_title(item)))
+    }
+    ItemSpec::Native(item) => ItemKey::Native(localized_title(item)),
+  }
+}
+
+#[cfg(target_os = "macos")]
+impl Menu {
+  /// Update the menu to match the spec with as few changes as possible.
+  ///
+  /// Items matching the spec are kept, so `MenuItem`s and handles to them stay valid,
+  /// see `ItemSpec` for how items are matched. Submenus are reconciled recursively.
+  /// On error, the menu is left partially updated.
+  pub fn reconcile(&self, spec: &MenuSpec) -> Result<(), MenuError> {
+    let items = self.items();
+    let old_keys = unique_keys(items.iter().map(item_key));
+    let new_keys = unique_keys(spec.items.iter().map(spec_key));
+    let ns_menu = unsafe { self.as_raw() };
+    for op in diff(&old_keys, &new_keys) {
+      match op {
+        DiffOp::Remove { old } | DiffOp::Detach { old } => unsafe {
+          let () = msg_send![ns_menu, removeItemAtIndex: old as isize];
+        },
+        DiffOp::Move { old, new } => {
+          update_item(&items[old], &spec.items[new])?;
+          insert(ns_menu, &items[old], new);
+        }
+        DiffOp::Insert { new } => {
+          let item = make_item(&spec.items[new])?;
+          insert(ns_menu, &item, new);
+        }
+        DiffOp::Update { old, new } => update_item(&items[old], &spec.items[new])?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn insert(ns_menu: id, item: &MenuItem, index: usize) {
+  unsafe {
+    let () = msg_send![ns_menu, insertItem: item.as_raw() atIndex: index as isize];
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn make_item(spec: &ItemSpec) -> Result<MenuItem, MenuError> {
+  match spec {
+    ItemSpec::Item(spec) => {
+      let mut item = MenuItem::new(
+        &spec.title,
+        None,
+        spec
+          .key_equivalent
+          .as_ref()
+          .map(OwnedKeyEquivalent::as_key_equivalent),
+        spec.enabled,
+        spec.selected,
+        MenuType::MenuBar,
+      )?;
+      item.set_id(spec.id);
+      Ok(item)
+    }
+    ItemSpec::Native(native) => MenuItem::new_native(native.clone(), None, None, MenuType::MenuBar),
+    ItemSpec::Submenu { title, menu } => {
+      let submenu = Menu::try_new()?;
+      submenu.reconcile(menu)?;
+      submenu.make_submenu_item(title)
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn update_item(item: &MenuItem, spec: &ItemSpec) -> Result<(), MenuError> {
+  match spec {
+    ItemSpec::Item(spec) => {
+      item
+        .set_title(&spec.title)
+        .set_key_equivalent(
+          spec
+            .key_equivalent
+            .as_ref()
+            .map(OwnedKeyEquivalent::as_key_equivalent),
+        )
+        .set_enabled(spec.enabled)
+        .set_selected(spec.selected);
+    }
+    // Matched by title, nothing else can change
+    ItemSpec::Native(_) => (),
+    ItemSpec::Submenu { menu, .. } => {
+      if let Some(submenu) = item.submenu() {
+        submenu.reconcile(menu)?;
+      }
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Run the operations on a list of keys, as `Menu::reconcile` does on the items
+  fn apply<K: Clone + PartialEq + std::fmt::Debug>(old: &[K], new: &[K], ops: &[DiffOp]) -> Vec<K> {
+    let mut current = old.to_vec();
+    for op in ops {
+      match *op {
+        DiffOp::Remove { old: index } | DiffOp::Detach { old: index } => {
+          current.remove(index);
+        }
+        DiffOp::Move { old: from, new: to } => current.insert(to, old[from].clone()),
+        DiffOp::Insert { new: to } => current.insert(to, new[to].clone()),
+        DiffOp::Update { old: from, new: to } => {
+          assert_eq!(old[from], new[to]);
+          assert_eq!(current[to], new[to]);
+        }
+      }
+    }
+    current
+  }
+
+  fn check(old: &[char], new: &[char]) -> Vec<DiffOp> {
+    let ops = diff(old, new);
+    assert_eq!(
+      apply(old, new, &ops),
+      new,
+      "{:?} -> {:?}: {:?}",
+      old,
+      new,
+      ops
+    );
+    ops
+  }
+
+  fn count(ops: &[DiffOp], matches: fn(&DiffOp) -> bool) -> usize {
+    ops.iter().filter(|op| matches(op)).count()
+  }
+
+  #[test]
+  fn pure_inserts() {
+    let ops = check(&['b', 'd'], &['a', 'b', 'c', 'd', 'e']);
+    assert_eq!(
+      ops,
+      vec![
+        DiffOp::Insert { new: 0 },
+        DiffOp::Update { old: 0, new: 1 },
+        DiffOp::Insert { new: 2 },
+        DiffOp::Update { old: 1, new: 3 },
+        DiffOp::Insert { new: 4 },
+      ]
+    );
+  }
+
+  #[test]
+  fn pure_removes() {
+    let ops = check(&['a', 'b', 'c', 'd', 'e'], &['b', 'd']);
+    assert_eq!(
+      ops,
+      vec![
+        DiffOp::Remove { old: 4 },
+        DiffOp::Remove { old: 2 },
+        DiffOp::Remove { old: 0 },
+        DiffOp::Update { old: 1, new: 0 },
+        DiffOp::Update { old: 3, new: 1 },
+      ]
+    );
+  }
+
+  #[test]
+  fn empty_lists() {
+    assert_eq!(check(&[], &[]), vec![]);
+    assert_eq!(
+      check(&[], &['a', 'b']),
+      vec![DiffOp::Insert { new: 0 }, DiffOp::Insert { new: 1 }]
+    );
+    assert_eq!(
+      check(&['a', 'b'], &[]),
+      vec![DiffOp::Remove { old: 1 }, DiffOp::Remove { old: 0 }]
+    );
+  }
+
+  #[test]
+  fn only_items_outside_the_longest_run_move() {
+    // Moving the first item to the end keeps the others in place
+    let ops = check(&['a', 'b', 'c', 'd'], &['b', 'c', 'd', 'a']);
+    assert_eq!(
+      ops,
+      vec![
+        DiffOp::Detach { old: 0 },
+        DiffOp::Update { old: 1, new: 0 },
+        DiffOp::Update { old: 2, new: 1 },
+        DiffOp::Update { old: 3, new: 2 },
+        DiffOp::Move { old: 0, new: 3 },
+      ]
+    );
+    // Reversing keeps a single item
+    let ops = check(&['a', 'b', 'c', 'd'], &['d', 'c', 'b', 'a']);
+    assert_eq!(count(&ops, |op| matches!(op, DiffOp::Move { .. })), 3);
+    assert_eq!(count(&ops, |op| matches!(op, DiffOp::Update { .. })), 1);
+    // Swapping two items far apart moves both, the run between them stays
+    let ops = check(&['a', 'b', 'c', 'd', 'e'], &['e', 'b', 'c', 'd', 'a']);
+    assert_eq!(count(&ops, |op| matches!(op, DiffOp::Move { .. })), 2);
+  }
+
+  #[test]
+  fn longest_increasing_run_positions() {
+    assert_eq!(longest_increasing_run(&[]), Vec::<usize>::new());
+    assert_eq!(longest_increasing_run(&[3, 2, 1]).len(), 1);
+    assert_eq!(
+      longest_increasing_run(&[0, 8, 4, 12, 2, 10, 6, 14]).len(),
+      4
+    );
+    assert_eq!(
+      longest_increasing_run(&[1, 3, 0, 4, 2, 5]),
+      vec![0, 1, 3, 5]
+    );
+  }
+
+  #[test]
+  fn duplicate_keys_are_numbered() {
+    let keys = unique_keys(
+      [
+        ItemKey::Separator,
+        ItemKey::Native("Copy".into()),
+        ItemKey::Separator,
+        ItemKey::Native("Copy".into()),
+        ItemKey::Separator,
+      ]
+      .into_iter(),
+    );
+    assert_eq!(
+      keys.iter().map(|(_, count)| *count).collect::<Vec<_>>(),
+      vec![0, 0, 1, 1, 2]
+    );
+
+    // Separators are matched in order, the extra one is removed
+    let old = unique_keys(
+      [
+        ItemKey::Id(MenuId(1)),
+        ItemKey::Separator,
+        ItemKey::Id(MenuId(2)),
+        ItemKey::Separator,
+        ItemKey::Id(MenuId(3)),
+      ]
+      .into_iter(),
+    );
+    let new = unique_keys(
+      [
+        ItemKey::Id(MenuId(2)),
+        ItemKey::Separator,
+        ItemKey::Id(MenuId(3)),
+      ]
+      .into_iter(),
+    );
+    let ops = diff(&old, &new);
+    assert_eq!(apply(&old, &new, &ops), new);
+    assert_eq!(
+      count(&ops, |op| matches!(op, DiffOp::Remove { .. })),
+      2,
+      "{:?}",
+      ops
+    );
+  }
+
+  #[test]
+  fn services_is_matched_as_a_native_item() {
+    let spec = [
+      ItemSpec::Submenu {
+        title: "Services".into(),
+        menu: MenuSpec::new(),
+      },
+      ItemSpec::Native(NativeMenuItemType::Services),
+      ItemSpec::Native(NativeMenuItemType::Separator),
+    ];
+    let new = unique_keys(spec.iter().map(spec_key));
+    assert_eq!(
+      new,
+      vec![
+        (ItemKey::Submenu("Services".into()), 0),
+        (ItemKey::Native("Services".into()), 0),
+        (ItemKey::Separator, 0),
+      ]
+    );
+    // As `item_key` reads the native Services item back from the menu
+    let old = unique_keys(
+      [
+        ItemKey::Native("Services".into()),
+        ItemKey::Separator,
+        ItemKey::Submenu("Services".into()),
+      ]
+      .into_iter(),
+    );
+    let ops = diff(&old, &new);
+    assert_eq!(apply(&old, &new, &ops), new);
+    assert_eq!(
+      count(&ops, |op| matches!(op, DiffOp::Insert { .. })),
+      0,
+      "{:?}",
+      ops
+    );
+  }
+
+  // Small deterministic generator, good enough to shuffle lists
+  struct XorShift(u64);
+
+  impl XorShift {
+    fn next(&mut self, bound: usize) -> usize {
+      self.0 ^= self.0 << 13;
+      self.0 ^= self.0 >> 7;
+      self.0 ^= self.0 << 17;
+      (self.0 % bound as u64) as usize
+    }
+    fn list(&mut self) -> Vec<char> {
+      let mut list: Vec<char> = ('a'..='l').filter(|_| self.next(3) != 0).collect();
+      for i in (1..list.len()).rev() {
+        let j = self.next(i + 1);
+        list.swap(i, j);
+      }
+      list
+    }
+  }
+
+  #[test]
+  fn applying_the_diff_gives_the_new_list() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    for _ in 0..2000 {
+      let old = rng.list();
+      let new = rng.list();
+      let ops = check(&old, &new);
+      // Every kept item is either updated in place or moved, never both
+      let kept = old.iter().filter(|key| new.contains(key)).count();
+      let moved = count(&ops, |op| matches!(op, DiffOp::Move { .. }));
+      let updated = count(&ops, |op| matches!(op, DiffOp::Update { .. }));
+      assert_eq!(moved + updated, kept);
+      assert_eq!(moved, count(&ops, |op| matches!(op, DiffOp::Detach { .. })));
+    }
+  }
+}
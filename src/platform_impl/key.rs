@@ -1,7 +1,304 @@
-use cocoa::appkit::NSEventModifierFlags;
+#[cfg(target_os = "macos")]
+pub use cocoa::appkit::NSEventModifierFlags;
+
+#[cfg(not(target_os = "macos"))]
+pub use modifier_flags::NSEventModifierFlags;
+
+#[cfg(not(target_os = "macos"))]
+#[allow(non_upper_case_globals)]
+mod modifier_flags {
+  bitflags::bitflags! {
+    /// Same masks as `cocoa::appkit::NSEventModifierFlags`, which only builds on macOS.
+    pub struct NSEventModifierFlags: u64 {
+      const NSAlphaShiftKeyMask = 1 << 16;
+      const NSShiftKeyMask = 1 << 17;
+      const NSControlKeyMask = 1 << 18;
+      const NSAlternateKeyMask = 1 << 19;
+      const NSCommandKeyMask = 1 << 20;
+      const NSNumericPadKeyMask = 1 << 21;
+      const NSHelpKeyMask = 1 << 22;
+      const NSFunctionKeyMask = 1 << 23;
+      const NSDeviceIndependentModifierFlagsMask = 0xffff0000;
+    }
+  }
+}
 
 #[derive(Debug, Clone)]
 pub struct KeyEquivalent<'a> {
   pub key: &'a str,
   pub masks: Option<NSEventModifierFlags>,
 }
+
+impl KeyEquivalent<'_> {
+  pub fn into_owned(self) -> OwnedKeyEquivalent {
+    OwnedKeyEquivalent {
+      key: self.key.to_string(),
+      masks: self.masks.unwrap_or_else(NSEventModifierFlags::empty),
+    }
+  }
+  /// Format the shortcut like AppKit shows it next to the item, such as `⇧⌘S` or `Shift+Cmd+S`.
+  ///
+  /// Modifiers come in the macOS order, control, option, shift then command,
+  /// and an uppercase letter implies shift.
+  pub fn display(&self, style: Style) -> String {
+    let mut masks = self.masks.unwrap_or_else(NSEventModifierFlags::empty);
+    let mut chars = self.key.chars();
+    let key = match (chars.next(), chars.next()) {
+      (Some(c), None) => {
+        if c.is_uppercase() {
+          masks |= NSEventModifierFlags::NSShiftKeyMask;
+        }
+        match key_name(c, style) {
+          Some(name) => name.to_string(),
+          None => c.to_uppercase().collect(),
+        }
+      }
+      _ => self.key.to_uppercase(),
+    };
+
+    let mut parts: Vec<&str> = MODIFIER_LABELS
+      .iter()
+      .filter(|(mask, _, _)| masks.contains(*mask))
+      .map(|(_, symbol, text)| match style {
+        Style::MacSymbols => *symbol,
+        Style::Text => *text,
+      })
+      .collect();
+    parts.push(&key);
+    match style {
+      Style::MacSymbols => parts.concat(),
+      Style::Text => parts.join("+"),
+    }
+  }
+}
+
+/// How `KeyEquivalent::display` formats a shortcut.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Style {
+  /// Symbols like the ones of macOS menus, such as `⇧⌘S`.
+  MacSymbols,
+  /// Words joined with `+`, such as `Shift+Cmd+S`.
+  Text,
+}
+
+// In display order, as mask, symbol and text
+const MODIFIER_LABELS: [(NSEventModifierFlags, &str, &str); 5] = [
+  (NSEventModifierFlags::NSFunctionKeyMask, "fn", "Fn"),
+  (NSEventModifierFlags::NSControlKeyMask, "⌃", "Ctrl"),
+  (NSEventModifierFlags::NSAlternateKeyMask, "⌥", "Option"),
+  (NSEventModifierFlags::NSShiftKeyMask, "⇧", "Shift"),
+  (NSEventModifierFlags::NSCommandKeyMask, "⌘", "Cmd"),
+];
+
+// Name of the keys AppKit shows with a glyph, see NSEvent function key unicodes
+fn key_name(key: char, style: Style) -> Option<&'static str> {
+  let (symbol, text) = match key {
+    '\u{8}' | '\u{7f}' => ("⌫", "Backspace"),
+    '\u{f728}' => ("⌦", "Delete"),
+    '\r' => ("↩", "Return"),
+    '\u{3}' => ("⌤", "Enter"),
+    '\u{1b}' => ("⎋", "Esc"),
+    '\t' => ("⇥", "Tab"),
+    '\u{19}' => ("⇤", "Backtab"),
+    ' ' => ("Space", "Space"),
+    '\u{f700}' => ("↑", "Up"),
+    '\u{f701}' => ("↓", "Down"),
+    '\u{f702}' => ("←", "Left"),
+    '\u{f703}' => ("→", "Right"),
+    '\u{f729}' => ("↖", "Home"),
+    '\u{f72b}' => ("↘", "End"),
+    '\u{f72c}' => ("⇞", "PageUp"),
+    '\u{f72d}' => ("⇟", "PageDown"),
+    '\u{f739}' => ("⌧", "Clear"),
+    // NSF1FunctionKey to NSF35FunctionKey
+    '\u{f704}'..='\u{f726}' => {
+      let name = FUNCTION_KEY_NAMES[(key as u32 - 0xf704) as usize];
+      (name, name)
+    }
+    _ => return None,
+  };
+  Some(match style {
+    Style::MacSymbols => symbol,
+    Style::Text => text,
+  })
+}
+
+const FUNCTION_KEY_NAMES: [&str; 35] = [
+  "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15",
+  "F16", "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24", "F25", "F26", "F27", "F28", "F29",
+  "F30", "F31", "F32", "F33", "F34", "F35",
+];
+
+/// `KeyEquivalent` owning its key, such as the one read back from a menu item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedKeyEquivalent {
+  pub key: String,
+  /// Serialized as a list of names, such as `["shift", "command"]`.
+  #[cfg_attr(feature = "serde", serde(with = "serde_masks"))]
+  pub masks: NSEventModifierFlags,
+}
+
+impl OwnedKeyEquivalent {
+  pub fn as_key_equivalent(&self) -> KeyEquivalent<'_> {
+    KeyEquivalent {
+      key: &self.key,
+      masks: Some(self.masks),
+    }
+  }
+  /// See `KeyEquivalent::display`.
+  pub fn display(&self, style: Style) -> String {
+    self.as_key_equivalent().display(style)
+  }
+}
+
+/// Names of the modifier masks, as used in serialized and persisted shortcuts.
+pub(crate) const MODIFIER_NAMES: [(&str, NSEventModifierFlags); 8] = [
+  ("caps_lock", NSEventModifierFlags::NSAlphaShiftKeyMask),
+  ("shift", NSEventModifierFlags::NSShiftKeyMask),
+  ("control", NSEventModifierFlags::NSControlKeyMask),
+  ("option", NSEventModifierFlags::NSAlternateKeyMask),
+  ("command", NSEventModifierFlags::NSCommandKeyMask),
+  ("numeric_pad", NSEventModifierFlags::NSNumericPadKeyMask),
+  ("help", NSEventModifierFlags::NSHelpKeyMask),
+  ("function", NSEventModifierFlags::NSFunctionKeyMask),
+];
+
+#[cfg(feature = "serde")]
+mod serde_masks {
+  use super::{NSEventModifierFlags, MODIFIER_NAMES};
+  use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+  pub fn serialize<S: Serializer>(
+    masks: &NSEventModifierFlags,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    let names: Vec<&str> = MODIFIER_NAMES
+      .iter()
+      .filter(|(_, mask)| masks.contains(*mask))
+      .map(|(name, _)| *name)
+      .collect();
+    names.serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<NSEventModifierFlags, D::Error> {
+    let names: Vec<String> = Vec::deserialize(deserializer)?;
+    names
+      .iter()
+      .try_fold(NSEventModifierFlags::empty(), |masks, name| {
+        MODIFIER_NAMES
+          .iter()
+          .find(|(known, _)| known == name)
+          .map(|(_, mask)| masks | *mask)
+          .ok_or_else(|| D::Error::custom(format!("unknown modifier `{}`", name)))
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CONTROL: NSEventModifierFlags = NSEventModifierFlags::NSControlKeyMask;
+  const OPTION: NSEventModifierFlags = NSEventModifierFlags::NSAlternateKeyMask;
+  const SHIFT: NSEventModifierFlags = NSEventModifierFlags::NSShiftKeyMask;
+  const COMMAND: NSEventModifierFlags = NSEventModifierFlags::NSCommandKeyMask;
+  const FUNCTION: NSEventModifierFlags = NSEventModifierFlags::NSFunctionKeyMask;
+
+  #[test]
+  fn modifiers_are_in_macos_order() {
+    // Key, masks, symbols, text
+    let cases = [
+      ("s", COMMAND, "⌘S", "Cmd+S"),
+      ("s", SHIFT | COMMAND, "⇧⌘S", "Shift+Cmd+S"),
+      (
+        "s",
+        COMMAND | SHIFT | OPTION | CONTROL,
+        "⌃⌥⇧⌘S",
+        "Ctrl+Option+Shift+Cmd+S",
+      ),
+      ("s", COMMAND | CONTROL, "⌃⌘S", "Ctrl+Cmd+S"),
+      ("s", COMMAND | OPTION, "⌥⌘S", "Option+Cmd+S"),
+      ("f", FUNCTION | CONTROL, "fn⌃F", "Fn+Ctrl+F"),
+      // Uppercase implies shift
+      ("S", COMMAND, "⇧⌘S", "Shift+Cmd+S"),
+      ("S", SHIFT | COMMAND, "⇧⌘S", "Shift+Cmd+S"),
+      ("a", NSEventModifierFlags::empty(), "A", "A"),
+      ("é", OPTION, "⌥É", "Option+É"),
+      // Masks that aren't shown
+      (
+        "1",
+        COMMAND | NSEventModifierFlags::NSNumericPadKeyMask,
+        "⌘1",
+        "Cmd+1",
+      ),
+    ];
+    for (key, masks, symbols, text) in cases {
+      let key_equivalent = KeyEquivalent {
+        key,
+        masks: Some(masks),
+      };
+      assert_eq!(key_equivalent.display(Style::MacSymbols), symbols);
+      assert_eq!(key_equivalent.display(Style::Text), text);
+      assert_eq!(
+        key_equivalent.into_owned().display(Style::MacSymbols),
+        symbols
+      );
+    }
+    let no_masks = KeyEquivalent {
+      key: "q",
+      masks: None,
+    };
+    assert_eq!(no_masks.display(Style::Text), "Q");
+  }
+
+  #[test]
+  fn key_names() {
+    // Key, symbol, text
+    let cases = [
+      ('\u{8}', "⌫", "Backspace"),
+      ('\u{7f}', "⌫", "Backspace"),
+      ('\u{f728}', "⌦", "Delete"),
+      ('\r', "↩", "Return"),
+      ('\u{3}', "⌤", "Enter"),
+      ('\u{1b}', "⎋", "Esc"),
+      ('\t', "⇥", "Tab"),
+      ('\u{19}', "⇤", "Backtab"),
+      (' ', "Space", "Space"),
+      ('\u{f700}', "↑", "Up"),
+      ('\u{f701}', "↓", "Down"),
+      ('\u{f702}', "←", "Left"),
+      ('\u{f703}', "→", "Right"),
+      ('\u{f729}', "↖", "Home"),
+      ('\u{f72b}', "↘", "End"),
+      ('\u{f72c}', "⇞", "PageUp"),
+      ('\u{f72d}', "⇟", "PageDown"),
+      ('\u{f739}', "⌧", "Clear"),
+      ('\u{f704}', "F1", "F1"),
+      ('\u{f70f}', "F12", "F12"),
+      ('\u{f726}', "F35", "F35"),
+    ];
+    for (key, symbol, text) in cases {
+      assert_eq!(key_name(key, Style::MacSymbols), Some(symbol), "{:?}", key);
+      assert_eq!(key_name(key, Style::Text), Some(text), "{:?}", key);
+    }
+    for key in ['a', 'A', '1', '\u{f727}', '\u{f72a}'] {
+      assert_eq!(key_name(key, Style::Text), None, "{:?}", key);
+    }
+
+    let key_equivalent = KeyEquivalent {
+      key: "\u{f700}",
+      masks: Some(OPTION | COMMAND),
+    };
+    assert_eq!(key_equivalent.display(Style::MacSymbols), "⌥⌘↑");
+    assert_eq!(key_equivalent.display(Style::Text), "Option+Cmd+Up");
+    // Keys of several characters are uppercased as is
+    let key_equivalent = KeyEquivalent {
+      key: "ab",
+      masks: Some(COMMAND),
+    };
+    assert_eq!(key_equivalent.display(Style::Text), "Cmd+AB");
+  }
+}
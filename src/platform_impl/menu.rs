@@ -1,9 +1,16 @@
-use cocoa::base::id;
-use objc::runtime::Object;
+#[cfg(target_os = "macos")]
+use cocoa::{
+  appkit::NSApp,
+  base::{id, nil, BOOL, NO},
+};
+#[cfg(target_os = "macos")]
+use objc::{class, msg_send, runtime::Object, sel, sel_impl};
 use std::{
   collections::hash_map::DefaultHasher,
   hash::{Hash, Hasher},
 };
+#[cfg(target_os = "macos")]
+use std::{ffi::CStr, os::raw::c_char};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MenuType {
@@ -11,11 +18,88 @@ pub enum MenuType {
   ContextMenu,
 }
 
+#[cfg(target_os = "macos")]
+pub fn is_main_thread() -> bool {
+  unsafe {
+    let is_main_thread: BOOL = msg_send![class!(NSThread), isMainThread];
+    is_main_thread != NO
+  }
+}
+
+/// AppKit objects can only be used on the main thread, use a `MenuHandle` on other threads.
+#[cfg(target_os = "macos")]
+pub fn assert_main_thread() {
+  assert!(
+    is_main_thread(),
+    "menus can only be used on the main thread, use a MenuHandle on other threads"
+  );
+}
+
+/// How a menu item was triggered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MenuTrigger {
+  Mouse,
+  /// The key equivalent of the item, or its chord, was pressed.
+  Keyboard,
+  /// `MenuItem::activate` or `Menu::perform_action_by_id`.
+  Programmatic,
+  /// Triggered by something else, such as accessibility tools.
+  Other,
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn ns_string_to_string(ns_string: id) -> String {
+  if ns_string == nil {
+    return String::new();
+  }
+  let utf8: *const c_char = msg_send![ns_string, UTF8String];
+  CStr::from_ptr(utf8).to_string_lossy().into_owned()
+}
+
+/// System role of a menu, registered with `NSApplication`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MenuRole {
+  /// The application menu, the first submenu of the menu bar.
+  App,
+  /// macOS appends the list of open windows to this menu.
+  Window,
+  /// macOS adds the Help search field to this menu.
+  Help,
+  /// macOS fills this menu with the services available to the app.
+  Services,
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_menu_role(ns_menu: id, role: MenuRole) {
+  unsafe {
+    let app = NSApp();
+    match role {
+      MenuRole::App => {
+        // `setAppleMenu:` is not part of the public headers, check it is still around.
+        let responds: BOOL = msg_send![app, respondsToSelector: sel!(setAppleMenu:)];
+        if responds != NO {
+          let () = msg_send![app, setAppleMenu: ns_menu];
+        }
+      }
+      MenuRole::Window => {
+        let () = msg_send![app, setWindowsMenu: ns_menu];
+      }
+      MenuRole::Help => {
+        let () = msg_send![app, setHelpMenu: ns_menu];
+      }
+      MenuRole::Services => {
+        let () = msg_send![app, setServicesMenu: ns_menu];
+      }
+    }
+  }
+}
+
 /// Identifier of a custom menu item.
 ///
 /// Whenever you receive an event arising from a particular menu, this event contains a `MenuId` which
 /// identifies its origin.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MenuId(pub u16);
 
 impl From<MenuId> for u16 {
@@ -50,6 +134,11 @@ fn hash_string_to_u16(title: &str) -> u16 {
 pub struct Id(pub usize);
 
 impl Id {
+  /// Id matching no window, like `winit::window::WindowId::dummy`.
+  ///
+  /// # Safety
+  ///
+  /// The id must not be passed where the id of a live window is expected.
   pub const unsafe fn dummy() -> Self {
     Id(0)
   }
@@ -57,6 +146,7 @@ impl Id {
 
 // Convert the `cocoa::base::id` associated with a window to a usize to use as a unique identifier
 // for the window.
+#[cfg(target_os = "macos")]
 pub fn get_window_id(window_cocoa_id: id) -> Id {
   Id(window_cocoa_id as *const Object as _)
 }
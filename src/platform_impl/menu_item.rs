@@ -1,15 +1,21 @@
 use crate::{
+  accessibility::AccessibilityRole,
+  error::MenuError,
   event::Event,
-  event_channel::get_event_channel,
+  event_channel::dispatch_event,
+  menu::Menu,
   platform_impl::{
-    key,
-    menu::{get_window_id, MenuId, MenuType},
+    key::{self, OwnedKeyEquivalent},
+    menu::{get_window_id, is_main_thread, ns_string_to_string, MenuId, MenuTrigger, MenuType},
+    menu_item_attributes::MenuItemAttributes,
     native_menu_item_type::{make_native_menu_item, NativeMenuItemType},
+    retained::Retained,
   },
+  validation::validate,
 };
 use cocoa::{
-  appkit::{NSButton, NSEventModifierFlags, NSMenuItem},
-  base::{id, nil, NO, YES},
+  appkit::{NSButton, NSEventModifierFlags, NSEventType, NSMenuItem},
+  base::{id, nil, BOOL, NO, YES},
   foundation::NSString,
 };
 use objc::{
@@ -19,13 +25,47 @@ use objc::{
   runtime::{Class, Object, Sel},
   sel, sel_impl,
 };
-use std::sync::Once;
+use once_cell::sync::OnceCell;
+use std::{
+  cell::Cell,
+  ffi::c_void,
+  panic::{catch_unwind, AssertUnwindSafe},
+};
 
 static MENU_IDENTITY: &str = "MenuItemIdentity";
 
+// Key of the font of the title, associated with any `NSMenuItem` as native items don't have our
+// ivars. Only its address matters.
+static TITLE_FONT_KEY: u8 = 0;
+// OBJC_ASSOCIATION_RETAIN_NONATOMIC
+const ASSOCIATION_RETAIN: usize = 1;
+
+extern "C" {
+  fn objc_setAssociatedObject(object: id, key: *const c_void, value: id, policy: usize);
+  fn objc_getAssociatedObject(object: id, key: *const c_void) -> id;
+}
+
+// Object class names are global to the process, so the name carries the crate name and version
+// to not clash with classes registered by tao, other menu crates or other versions of this crate.
+const MENU_ITEM_CLASS_PREFIX: &str = concat!(
+  env!("CARGO_PKG_NAME"),
+  "_",
+  env!("CARGO_PKG_VERSION"),
+  "_MenuItem"
+);
+
+thread_local! {
+  // Set while `activate_menu_item` sends the action, to report its trigger
+  static ACTIVATING: Cell<Option<MenuTrigger>> = const { Cell::new(None) };
+}
+
+// How many suffixed names are tried when the class name is taken by an incompatible class.
+const MENU_ITEM_CLASS_ATTEMPTS: usize = 16;
+
+/// Only usable on the main thread, use `MenuItem::handle` to update it from other threads.
 #[derive(Debug, Clone)]
 pub struct MenuItem {
-  pub ns_menu_item: id,
+  pub(crate) ns_menu_item: Retained,
   menu_item_id: Option<MenuId>,
 }
 
@@ -33,6 +73,15 @@ impl MenuItem {
   pub fn id(&self) -> MenuId {
     self.menu_item_id.unwrap_or(MenuId::EMPTY)
   }
+  /// Get the underlying `NSMenuItem`.
+  ///
+  /// # Safety
+  ///
+  /// The pointer is only valid as long as this item, or one of its clones, is alive.
+  /// Retain it to keep it longer.
+  pub unsafe fn as_raw(&self) -> id {
+    self.ns_menu_item.as_ptr()
+  }
   pub fn new(
     title: &str,
     selector: Option<Sel>,
@@ -40,10 +89,13 @@ impl MenuItem {
     enabled: bool,
     selected: bool,
     menu_type: MenuType,
-  ) -> Self {
-    let (menu_item_id, menu_item) = make_menu_item(title, selector, key_equivalent, menu_type);
+  ) -> Result<Self, MenuError> {
+    if !is_main_thread() {
+      return Err(MenuError::NotMainThread);
+    }
+    let (menu_item_id, menu_item) = make_menu_item(title, selector, key_equivalent, menu_type)?;
     unsafe {
-      (&mut *menu_item).set_ivar(MENU_IDENTITY, menu_item_id.unwrap().0);
+      (&mut *menu_item).set_ivar(MENU_IDENTITY, menu_item_id.0);
       let _: () = msg_send![&*menu_item, setTarget:&*menu_item];
 
       if selected {
@@ -53,20 +105,191 @@ impl MenuItem {
         let () = msg_send![menu_item, setEnabled: NO];
       }
     }
-    Self {
-      ns_menu_item: menu_item,
-      menu_item_id,
+    Ok(Self {
+      ns_menu_item: unsafe { Retained::from_owned(menu_item) },
+      menu_item_id: Some(menu_item_id),
+    })
+  }
+  /// Custom item with the title, state and accessibility metadata of the attributes.
+  pub(crate) fn from_attributes(
+    attributes: MenuItemAttributes,
+    menu_type: MenuType,
+  ) -> Result<Self, MenuError> {
+    let menu_item = Self::new(
+      attributes.title,
+      attributes.selector,
+      attributes.key_equivalent,
+      attributes.enabled,
+      attributes.selected,
+      menu_type,
+    )?;
+    if let Some(label) = attributes.accessibility_label {
+      menu_item.set_accessibility_label(label);
+    }
+    if let Some(help) = attributes.accessibility_help {
+      menu_item.set_accessibility_help(help);
+    }
+    if let Some(role) = attributes.accessibility_role {
+      menu_item.set_accessibility_role(Some(role));
     }
+    Ok(menu_item)
   }
   pub fn new_native(
     item: NativeMenuItemType,
     title: Option<&str>,
     key_equivalent: Option<key::KeyEquivalent>,
     menu_type: MenuType,
-  ) -> Self {
+  ) -> Result<Self, MenuError> {
+    if !is_main_thread() {
+      return Err(MenuError::NotMainThread);
+    }
+    let (menu_item_id, menu_item) = make_native_menu_item(item, title, key_equivalent, menu_type)?;
+    if let Some(menu_id) = menu_item_id {
+      unsafe {
+        (&mut *menu_item).set_ivar(MENU_IDENTITY, menu_id.0);
+        let _: () = msg_send![&*menu_item, setTarget:&*menu_item];
+      }
+    }
+    Ok(Self {
+      ns_menu_item: unsafe { Retained::from_owned(menu_item) },
+      menu_item_id,
+    })
+  }
+  /// Replace the id of a custom item, does nothing for native items.
+  pub(crate) fn set_id(&mut self, menu_id: MenuId) {
+    if self.menu_item_id.is_some() {
+      unsafe {
+        (*self.ns_menu_item.as_ptr()).set_ivar(MENU_IDENTITY, menu_id.0);
+      }
+      self.menu_item_id = Some(menu_id);
+    }
+  }
+  /// Wrap an item read back from a menu.
+  pub(crate) unsafe fn from_raw(item: id) -> Self {
     Self {
-      ns_menu_item: make_native_menu_item(item, title, key_equivalent, menu_type),
-      menu_item_id: None,
+      ns_menu_item: Retained::retain(item),
+      menu_item_id: custom_menu_item_id(item),
+    }
+  }
+  pub fn title(&self) -> String {
+    unsafe {
+      let title: id = msg_send![self.ns_menu_item.as_ptr(), title];
+      ns_string_to_string(title)
+    }
+  }
+  pub fn is_enabled(&self) -> bool {
+    unsafe {
+      let enabled: BOOL = msg_send![self.ns_menu_item.as_ptr(), isEnabled];
+      enabled != NO
+    }
+  }
+  pub fn is_selected(&self) -> bool {
+    unsafe {
+      let state: isize = msg_send![self.ns_menu_item.as_ptr(), state];
+      state == 1
+    }
+  }
+  pub fn is_separator(&self) -> bool {
+    unsafe {
+      let is_separator: BOOL = msg_send![self.ns_menu_item.as_ptr(), isSeparatorItem];
+      is_separator != NO
+    }
+  }
+  /// None if the item has no key equivalent.
+  pub fn key_equivalent(&self) -> Option<OwnedKeyEquivalent> {
+    unsafe {
+      let key: id = msg_send![self.ns_menu_item.as_ptr(), keyEquivalent];
+      let key = ns_string_to_string(key);
+      if key.is_empty() {
+        return None;
+      }
+      let masks: NSEventModifierFlags =
+        msg_send![self.ns_menu_item.as_ptr(), keyEquivalentModifierMask];
+      Some(OwnedKeyEquivalent { key, masks })
+    }
+  }
+  /// The submenu opened by this item, such as the ones added with `Menu::add_submenu`.
+  pub fn submenu(&self) -> Option<Menu> {
+    unsafe {
+      let submenu: id = msg_send![self.ns_menu_item.as_ptr(), submenu];
+      match submenu == nil {
+        true => None,
+        false => Some(Menu::from_raw(submenu)),
+      }
+    }
+  }
+  /// Trigger the item as if it was clicked, custom items send the same menu event as a click.
+  ///
+  /// Custom items send their event right away, without a running application. Native items
+  /// send their action through the responder chain, which needs a running `NSApplication`.
+  ///
+  /// Returns false if the item is disabled, has no action, or nothing handled the action.
+  pub fn activate(&self) -> bool {
+    unsafe { activate_menu_item(self.ns_menu_item.as_ptr()) }
+  }
+  pub(crate) fn activate_as(&self, trigger: MenuTrigger) -> bool {
+    unsafe { activate_menu_item_as(self.ns_menu_item.as_ptr(), trigger) }
+  }
+  /// Show a second line of text under the title, empty to remove it.
+  ///
+  /// Requires macOS 14.4, does nothing on older versions, see `supports_subtitles`.
+  pub fn set_subtitle(&self, subtitle: &str) -> &Self {
+    if supports_subtitles() {
+      unsafe {
+        let () = msg_send![self.ns_menu_item.as_ptr(), setSubtitle: optional_ns_string(subtitle)];
+      }
+    }
+    self
+  }
+  /// Read by screen readers instead of the title, empty to use the title again.
+  pub fn set_accessibility_label(&self, label: &str) -> &Self {
+    unsafe {
+      let () =
+        msg_send![self.ns_menu_item.as_ptr(), setAccessibilityLabel: optional_ns_string(label)];
+    }
+    self
+  }
+  /// Read by screen readers after the label, empty to remove it.
+  pub fn set_accessibility_help(&self, help: &str) -> &Self {
+    unsafe {
+      let () =
+        msg_send![self.ns_menu_item.as_ptr(), setAccessibilityHelp: optional_ns_string(help)];
+    }
+    self
+  }
+  /// None to let AppKit pick the role again.
+  pub fn set_accessibility_role(&self, role: Option<AccessibilityRole>) -> &Self {
+    unsafe {
+      let role = optional_ns_string(role.map_or("", AccessibilityRole::as_ax_role));
+      let () = msg_send![self.ns_menu_item.as_ptr(), setAccessibilityRole: role];
+    }
+    self
+  }
+  /// None if the item uses its title.
+  pub fn accessibility_label(&self) -> Option<String> {
+    unsafe {
+      let label: id = msg_send![self.ns_menu_item.as_ptr(), accessibilityLabel];
+      Some(ns_string_to_string(label)).filter(|label| !label.is_empty())
+    }
+  }
+  /// None if the role isn't one of `AccessibilityRole`.
+  pub fn accessibility_role(&self) -> Option<AccessibilityRole> {
+    unsafe {
+      let role: id = msg_send![self.ns_menu_item.as_ptr(), accessibilityRole];
+      AccessibilityRole::from_ax_role(&ns_string_to_string(role))
+    }
+  }
+  pub fn accessibility_help(&self) -> Option<String> {
+    unsafe {
+      let help: id = msg_send![self.ns_menu_item.as_ptr(), accessibilityHelp];
+      Some(ns_string_to_string(help)).filter(|help| !help.is_empty())
+    }
+  }
+  /// Whether the item shows an image next to its title.
+  pub fn has_image(&self) -> bool {
+    unsafe {
+      let image: id = msg_send![self.ns_menu_item.as_ptr(), image];
+      image != nil
     }
   }
   pub fn set_enabled(&self, is_enabled: bool) -> &Self {
@@ -75,14 +298,38 @@ impl MenuItem {
         true => YES,
         false => NO,
       };
-      let () = msg_send![self.ns_menu_item, setEnabled: status];
+      let () = msg_send![self.ns_menu_item.as_ptr(), setEnabled: status];
     }
     self
   }
   pub fn set_title(&self, title: &str) -> &Self {
     unsafe {
       let menu_title = NSString::alloc(nil).init_str(title);
-      self.ns_menu_item.setTitle_(menu_title);
+      self.ns_menu_item.as_ptr().setTitle_(menu_title);
+      if title_font(self.ns_menu_item.as_ptr()) != nil {
+        apply_title_font(self.ns_menu_item.as_ptr());
+      }
+    }
+    self
+  }
+  /// None to remove the key equivalent.
+  pub fn set_key_equivalent(&self, key_equivalent: Option<key::KeyEquivalent>) -> &Self {
+    unsafe {
+      let (key, masks) = match key_equivalent {
+        Some(ke) => (
+          NSString::alloc(nil).init_str(ke.key),
+          ke.masks.unwrap_or_else(NSEventModifierFlags::empty),
+        ),
+        None => (
+          NSString::alloc(nil).init_str(""),
+          NSEventModifierFlags::empty(),
+        ),
+      };
+      let () = msg_send![self.ns_menu_item.as_ptr(), setKeyEquivalent: key];
+      self
+        .ns_menu_item
+        .as_ptr()
+        .setKeyEquivalentModifierMask_(masks);
     }
     self
   }
@@ -92,42 +339,195 @@ impl MenuItem {
         true => 1_isize,
         false => 0_isize,
       };
-      let () = msg_send![self.ns_menu_item, setState: state];
+      let () = msg_send![self.ns_menu_item.as_ptr(), setState: state];
     }
     self
   }
 }
 
+// nil for an empty string, to reset optional properties
+/// Keep `font` for the title of the item, also when the title changes. Nil to use the font of
+/// the menu again.
+pub(crate) unsafe fn set_title_font(item: id, font: id) {
+  let key = &TITLE_FONT_KEY as *const u8 as *const c_void;
+  objc_setAssociatedObject(item, key, font, ASSOCIATION_RETAIN);
+  apply_title_font(item);
+}
+
+unsafe fn title_font(item: id) -> id {
+  objc_getAssociatedObject(item, &TITLE_FONT_KEY as *const u8 as *const c_void)
+}
+
+unsafe fn apply_title_font(item: id) {
+  let font = title_font(item);
+  let attributed_title: id = match font == nil {
+    true => nil,
+    false => {
+      let title: id = msg_send![item, title];
+      let key = NSString::alloc(nil).init_str("NSFont");
+      let attributes: id = msg_send![class!(NSDictionary), dictionaryWithObject: font forKey: key];
+      let attributed_title: id = msg_send![class!(NSAttributedString), alloc];
+      let attributed_title: id =
+        msg_send![attributed_title, initWithString: title attributes: attributes];
+      msg_send![attributed_title, autorelease]
+    }
+  };
+  let () = msg_send![item, setAttributedTitle: attributed_title];
+}
+
+unsafe fn optional_ns_string(string: &str) -> id {
+  match string.is_empty() {
+    true => nil,
+    false => NSString::alloc(nil).init_str(string),
+  }
+}
+
 pub fn make_menu_item(
   title: &str,
   selector: Option<Sel>,
   key_equivalent: Option<key::KeyEquivalent>,
   menu_type: MenuType,
-) -> (Option<MenuId>, *mut Object) {
-  let alloc = make_menu_item_alloc();
+) -> Result<(MenuId, *mut Object), MenuError> {
+  let alloc = make_menu_item_alloc()?;
   let menu_id = MenuId::new(title);
 
   unsafe {
     let title = NSString::alloc(nil).init_str(title);
     let menu_item = make_menu_item_from_alloc(alloc, title, selector, key_equivalent, menu_type);
+    if menu_item == nil {
+      return Err(MenuError::AllocationFailed);
+    }
+
+    Ok((menu_id, menu_item))
+  }
+}
+
+/// Send the action of an item like AppKit does when it is clicked.
+pub(crate) unsafe fn activate_menu_item(item: id) -> bool {
+  activate_menu_item_as(item, MenuTrigger::Programmatic)
+}
+
+/// Like `activate_menu_item`, reporting another trigger in the menu event.
+pub(crate) unsafe fn activate_menu_item_as(item: id, trigger: MenuTrigger) -> bool {
+  let enabled: BOOL = msg_send![item, isEnabled];
+  let has_submenu: BOOL = msg_send![item, hasSubmenu];
+  let is_separator: BOOL = msg_send![item, isSeparatorItem];
+  if enabled == NO || has_submenu != NO || is_separator != NO {
+    return false;
+  }
+  let action: Sel = msg_send![item, action];
+  let was_activating = ACTIVATING.with(|activating| activating.replace(Some(trigger)));
+  // Custom items dispatch their event directly, so they can be triggered without
+  // a running application, such as in tests
+  let handled = match custom_menu_item_type(item, action) {
+    Some(menu_type) => {
+      send_event(&*item, menu_type);
+      true
+    }
+    None => {
+      let target: id = msg_send![item, target];
+      let app: id = msg_send![class!(NSApplication), sharedApplication];
+      let handled: BOOL = msg_send![app, sendAction: action to: target from: item];
+      handled != NO
+    }
+  };
+  ACTIVATING.with(|activating| activating.set(was_activating));
+  handled
+}
+
+// Menu type of a custom item sending menu events, None for other items
+unsafe fn custom_menu_item_type(item: id, action: Sel) -> Option<MenuType> {
+  custom_menu_item_id(item)?;
+  if action == sel!(fireMenubarAction:) {
+    Some(MenuType::MenuBar)
+  } else if action == sel!(fireStatusbarAction:) {
+    Some(MenuType::ContextMenu)
+  } else {
+    None
+  }
+}
 
-    (Some(menu_id), menu_item)
+/// Id of an item created with `MenuItem::new`, None for native items and items from elsewhere.
+pub(crate) unsafe fn custom_menu_item_id(item: id) -> Option<MenuId> {
+  let class = make_menu_item_class().ok()?;
+  let is_menu_item_class: BOOL = msg_send![item, isKindOfClass: class];
+  if is_menu_item_class == NO {
+    return None;
+  }
+  // Custom items are their own target, native items use the responder chain
+  let target: id = msg_send![item, target];
+  if target != item {
+    return None;
   }
+  let id: u16 = *(*item).get_ivar(MENU_IDENTITY);
+  Some(MenuId(id))
 }
 
-fn make_menu_item_alloc() -> *mut Object {
-  unsafe { msg_send![make_menu_item_class(), alloc] }
+/// Depth first search of a custom item in a menu and its submenus.
+pub(crate) unsafe fn find_menu_item(menu: id, menu_id: MenuId) -> Option<id> {
+  let count: isize = msg_send![menu, numberOfItems];
+  for index in 0..count {
+    let item: id = msg_send![menu, itemAtIndex: index];
+    if custom_menu_item_id(item) == Some(menu_id) {
+      return Some(item);
+    }
+    let submenu: id = msg_send![item, submenu];
+    if submenu != nil {
+      if let Some(item) = find_menu_item(submenu, menu_id) {
+        return Some(item);
+      }
+    }
+  }
+  None
+}
+
+fn make_menu_item_alloc() -> Result<*mut Object, MenuError> {
+  let class = make_menu_item_class()?;
+  unsafe { Ok(msg_send![class, alloc]) }
 }
 
-fn make_menu_item_class() -> *const Class {
-  static mut APP_CLASS: *const Class = 0 as *const Class;
-  static INIT: Once = Once::new();
+fn make_menu_item_class() -> Result<&'static Class, MenuError> {
+  static MENU_ITEM_CLASS: OnceCell<Result<&'static Class, MenuError>> = OnceCell::new();
 
-  INIT.call_once(|| unsafe {
-    let superclass = class!(NSMenuItem);
-    let mut decl = ClassDecl::new("MenuItem", superclass).unwrap();
-    decl.add_ivar::<u16>(MENU_IDENTITY);
+  MENU_ITEM_CLASS
+    .get_or_init(|| {
+      // The runtime acts as the registry: reuse a class with our name and our methods,
+      // or move on to the next suffixed name if it was registered by some other code.
+      let base_name = menu_item_class_base_name();
+      for attempt in 0..MENU_ITEM_CLASS_ATTEMPTS {
+        let name = match attempt {
+          0 => base_name.clone(),
+          _ => format!("{}_{}", base_name, attempt),
+        };
+        match ClassDecl::new(&name, class!(NSMenuItem)) {
+          Some(decl) => return Ok(register_menu_item_class(decl)),
+          None => {
+            if let Some(class) = Class::get(&name).filter(|class| is_menu_item_class(class)) {
+              return Ok(class);
+            }
+          }
+        }
+      }
+      Err(MenuError::ClassRegistration(base_name))
+    })
+    .clone()
+}
 
+// Object class names only allow letters, digits and underscores
+fn menu_item_class_base_name() -> String {
+  MENU_ITEM_CLASS_PREFIX
+    .chars()
+    .map(|c| match c.is_ascii_alphanumeric() {
+      true => c,
+      false => '_',
+    })
+    .collect()
+}
+
+fn register_menu_item_class(mut decl: ClassDecl) -> &'static Class {
+  decl.add_ivar::<u16>(MENU_IDENTITY);
+
+  unsafe {
     decl.add_method(
       sel!(dealloc),
       dealloc_custom_menuitem as extern "C" fn(&Object, _),
@@ -143,10 +543,55 @@ fn make_menu_item_class() -> *const Class {
       fire_status_bar_click as extern "C" fn(&Object, _, id),
     );
 
-    APP_CLASS = decl.register();
-  });
+    decl.add_method(
+      sel!(validateMenuItem:),
+      validate_menu_item as extern "C" fn(&Object, _, id) -> BOOL,
+    );
+
+    decl.add_method(
+      sel!(viewValueChanged:),
+      view_value_changed as extern "C" fn(&Object, _, id),
+    );
+  }
+
+  decl.register()
+}
 
-  unsafe { APP_CLASS }
+// A class is only compatible if its methods are the functions of this copy of the crate,
+// otherwise clicks would be sent to the event channel of another copy.
+fn is_menu_item_class(class: &Class) -> bool {
+  let has_method = |selector: Sel, function: usize| {
+    class
+      .instance_method(selector)
+      .map(|method| method.implementation() as usize == function)
+      .unwrap_or(false)
+  };
+  class.instance_variable(MENU_IDENTITY).is_some()
+    && has_method(
+      sel!(fireMenubarAction:),
+      fire_menu_bar_click as extern "C" fn(&Object, Sel, id) as usize,
+    )
+    && has_method(
+      sel!(fireStatusbarAction:),
+      fire_status_bar_click as extern "C" fn(&Object, Sel, id) as usize,
+    )
+    && has_method(
+      sel!(validateMenuItem:),
+      validate_menu_item as extern "C" fn(&Object, Sel, id) -> BOOL as usize,
+    )
+    && has_method(
+      sel!(viewValueChanged:),
+      view_value_changed as extern "C" fn(&Object, Sel, id) as usize,
+    )
+}
+
+/// Whether `MenuItem::set_subtitle` shows anything, from macOS 14.4.
+pub fn supports_subtitles() -> bool {
+  unsafe {
+    let responds: BOOL =
+      msg_send![class!(NSMenuItem), instancesRespondToSelector: sel!(setSubtitle:)];
+    responds != NO
+  }
 }
 
 fn make_menu_item_from_alloc(
@@ -184,12 +629,53 @@ fn make_menu_item_from_alloc(
   }
 }
 
+// Unwinding out of these callbacks is undefined behavior, panics of subscribers are dropped.
 extern "C" fn fire_menu_bar_click(this: &Object, _: Sel, _item: id) {
-  send_event(this, MenuType::MenuBar);
+  let _ = catch_unwind(AssertUnwindSafe(|| send_event(this, MenuType::MenuBar)));
 }
 
 extern "C" fn fire_status_bar_click(this: &Object, _: Sel, _item: id) {
-  send_event(this, MenuType::ContextMenu);
+  let _ = catch_unwind(AssertUnwindSafe(|| send_event(this, MenuType::ContextMenu)));
+}
+
+// Action of the controls of view items, see `MenuItemView`.
+extern "C" fn view_value_changed(this: &Object, _: Sel, sender: id) {
+  let (menu_id, value) = unsafe {
+    let id: u16 = *this.get_ivar(MENU_IDENTITY);
+    let value: f64 = msg_send![sender, doubleValue];
+    (MenuId(id), value)
+  };
+  let _ = catch_unwind(|| dispatch_event(Event::ViewValueChanged { menu_id, value }));
+}
+
+// Only called by AppKit when the parent menu auto enables its items.
+extern "C" fn validate_menu_item(this: &Object, _: Sel, _item: id) -> BOOL {
+  let menu_id = unsafe {
+    let id: u16 = *this.get_ivar(MENU_IDENTITY);
+    MenuId(id)
+  };
+
+  // Unwinding out of an Objective-C callback is undefined behavior, so a panicking handler
+  // is treated as if there was no handler.
+  let state = catch_unwind(AssertUnwindSafe(|| validate(menu_id))).unwrap_or(None);
+  match state {
+    Some(state) => unsafe {
+      let checked = match state.checked {
+        true => 1_isize,
+        false => 0_isize,
+      };
+      let () = msg_send![this, setState: checked];
+      if let Some(title) = state.title {
+        let menu_title = NSString::alloc(nil).init_str(&title);
+        let () = msg_send![this, setTitle: menu_title];
+      }
+      match state.enabled {
+        true => YES,
+        false => NO,
+      }
+    },
+    None => unsafe { msg_send![this, isEnabled] },
+  }
 }
 
 extern "C" fn dealloc_custom_menuitem(this: &Object, _: Sel) {
@@ -199,9 +685,6 @@ extern "C" fn dealloc_custom_menuitem(this: &Object, _: Sel) {
 }
 
 fn send_event(this: &Object, menu_type: MenuType) {
-  let channel = get_event_channel();
-  let tx = channel.0.clone();
-
   let menu_id = unsafe {
     let id: u16 = *this.get_ivar(MENU_IDENTITY);
     id
@@ -216,11 +699,75 @@ fn send_event(this: &Object, menu_type: MenuType) {
     MenuType::ContextMenu => None,
   };
 
+  let item = this as *const Object as id;
+  let (modifiers, trigger, checked, path) = unsafe {
+    let modifiers: NSEventModifierFlags = msg_send![class!(NSEvent), modifierFlags];
+    let state: isize = msg_send![item, state];
+    (
+      modifiers & NSEventModifierFlags::NSDeviceIndependentModifierFlagsMask,
+      current_trigger(),
+      state == 1,
+      submenu_path(item),
+    )
+  };
+
   let event = Event::MenuEvent {
     window_id,
     menu_id: MenuId(menu_id),
-    menu_type: menu_type,
+    menu_type,
+    modifiers,
+    trigger,
+    checked,
+    path,
   };
 
-  tx.send(event).unwrap();
+  dispatch_event(event);
+}
+
+unsafe fn current_trigger() -> MenuTrigger {
+  if let Some(trigger) = ACTIVATING.with(|activating| activating.get()) {
+    return trigger;
+  }
+  let app: id = msg_send![class!(NSApplication), sharedApplication];
+  let event: id = msg_send![app, currentEvent];
+  if event == nil {
+    return MenuTrigger::Other;
+  }
+  // Read the raw value, `NSEventType` doesn't cover every event type
+  let event_type: u64 = msg_send![event, type];
+  match event_type {
+    t if t == NSEventType::NSKeyDown as u64 => MenuTrigger::Keyboard,
+    t if t == NSEventType::NSLeftMouseDown as u64
+      || t == NSEventType::NSLeftMouseUp as u64
+      || t == NSEventType::NSRightMouseDown as u64
+      || t == NSEventType::NSRightMouseUp as u64
+      || t == NSEventType::NSOtherMouseDown as u64
+      || t == NSEventType::NSOtherMouseUp as u64 =>
+    {
+      MenuTrigger::Mouse
+    }
+    _ => MenuTrigger::Other,
+  }
+}
+
+// Titles of the items holding the submenus between the top level menu and the item
+unsafe fn submenu_path(item: id) -> Vec<String> {
+  let mut path = Vec::new();
+  let mut menu: id = msg_send![item, menu];
+  while menu != nil {
+    let supermenu: id = msg_send![menu, supermenu];
+    if supermenu == nil {
+      break;
+    }
+    let index: isize = msg_send![supermenu, indexOfItemWithSubmenu: menu];
+    if index < 0 {
+      break;
+    }
+    let parent: id = msg_send![supermenu, itemAtIndex: index];
+    let title: id = msg_send![parent, title];
+    path.push(ns_string_to_string(title));
+    menu = supermenu;
+  }
+  path.reverse();
+  path
 }
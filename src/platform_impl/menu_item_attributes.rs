@@ -1,4 +1,4 @@
-use crate::platform_impl::key::KeyEquivalent;
+use crate::{accessibility::AccessibilityRole, platform_impl::key::KeyEquivalent};
 use objc::runtime::Sel;
 
 #[derive(Debug, Clone)]
@@ -10,6 +10,12 @@ pub struct MenuItemAttributes<'a> {
   pub enabled: bool,
   /// Initial selected state. Default to false
   pub selected: bool,
+  /// Read by screen readers instead of the title. Default to None
+  pub accessibility_label: Option<&'a str>,
+  /// Read by screen readers after the label, such as what the item does. Default to None
+  pub accessibility_help: Option<&'a str>,
+  /// Default to None, read as a plain menu item
+  pub accessibility_role: Option<AccessibilityRole>,
 }
 
 impl<'a> MenuItemAttributes<'a> {
@@ -20,6 +26,9 @@ impl<'a> MenuItemAttributes<'a> {
       key_equivalent: None,
       enabled: true,
       selected: false,
+      accessibility_label: None,
+      accessibility_help: None,
+      accessibility_role: None,
     }
   }
   pub fn with_selector(mut self, selector: Sel) -> Self {
@@ -38,4 +47,16 @@ impl<'a> MenuItemAttributes<'a> {
     self.selected = selected;
     self
   }
+  pub fn with_accessibility_label(mut self, label: &'a str) -> Self {
+    self.accessibility_label = Some(label);
+    self
+  }
+  pub fn with_accessibility_help(mut self, help: &'a str) -> Self {
+    self.accessibility_help = Some(help);
+    self
+  }
+  pub fn with_accessibility_role(mut self, role: AccessibilityRole) -> Self {
+    self.accessibility_role = Some(role);
+    self
+  }
 }
@@ -1,5 +1,9 @@
 pub mod key;
 pub mod menu;
+#[cfg(target_os = "macos")]
 pub mod menu_item;
+#[cfg(target_os = "macos")]
 pub mod menu_item_attributes;
 pub mod native_menu_item_type;
+#[cfg(target_os = "macos")]
+pub mod retained;
@@ -1,9 +1,25 @@
-use crate::platform_impl::{key, menu::MenuType, menu_item::make_menu_item};
+#[cfg(target_os = "macos")]
+use crate::{
+  error::MenuError,
+  localization::localized_title,
+  platform_impl::{
+    key,
+    menu::{set_menu_role, MenuId, MenuRole, MenuType},
+    menu_item::make_menu_item,
+  },
+};
+#[cfg(target_os = "macos")]
 use cocoa::{
-  appkit::{NSEventModifierFlags, NSMenuItem},
-  base::{id, nil, selector},
+  appkit::{NSEventModifierFlags, NSMenu, NSMenuItem},
+  base::{id, nil, selector, BOOL, NO},
+  foundation::NSAutoreleasePool,
+};
+#[cfg(target_os = "macos")]
+use objc::{
+  class, msg_send,
+  runtime::{Object, Sel},
+  sel, sel_impl,
 };
-use objc::{class, msg_send, runtime::Object, sel, sel_impl};
 
 /// A menu item, bound to a pre-defined native action.
 ///
@@ -11,7 +27,8 @@ use objc::{class, msg_send, runtime::Object, sel, sel_impl};
 /// Unsupported variant will be no-op on such platform.
 ///
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NativeMenuItemType {
   About(String),
   CloseWindow,
@@ -30,27 +47,55 @@ pub enum NativeMenuItemType {
   Redo,
   SelectAll,
   Services,
+  BringAllToFront,
+  /// Custom item sending a menu event like the ones created with `add_item`, use its id to match the event.
+  Preferences,
+  Help(String),
+  Find,
+  FindNext,
+  FindPrevious,
+  Delete,
+  PasteAndMatchStyle,
+  ToggleTabBar,
+  SelectNextTab,
+  SelectPreviousTab,
+  ToggleSidebar,
+  PageSetup,
+  Print,
 }
 
+// Tags of `performFindPanelAction:`, see NSFindPanelAction
+#[cfg(target_os = "macos")]
+const FIND_PANEL_ACTION_SHOW_FIND_PANEL: isize = 1;
+#[cfg(target_os = "macos")]
+const FIND_PANEL_ACTION_NEXT: isize = 2;
+#[cfg(target_os = "macos")]
+const FIND_PANEL_ACTION_PREVIOUS: isize = 3;
+
+#[cfg(target_os = "macos")]
 pub fn make_native_menu_item(
   item: NativeMenuItemType,
   title: Option<&str>,
   key_equivalent: Option<key::KeyEquivalent>,
   menu_type: MenuType,
-) -> *mut Object {
+) -> Result<(Option<MenuId>, *mut Object), MenuError> {
+  let default_title = localized_title(&item);
+  let title = title.unwrap_or(default_title.as_str());
+
   let (_, menu_item) = match item {
-    NativeMenuItemType::Separator => unsafe { (None, NSMenuItem::separatorItem(nil)) },
-    NativeMenuItemType::About(app_name) => {
-      let _title = format!("About {}", app_name);
-      make_menu_item(
-        title.unwrap_or(_title.as_str()),
-        Some(selector("orderFrontStandardAboutPanel:")),
-        key_equivalent,
-        menu_type,
-      )
-    }
+    // Retain the autoreleased separator so every item is returned owned
+    NativeMenuItemType::Separator => unsafe {
+      let separator: *mut Object = msg_send![NSMenuItem::separatorItem(nil), retain];
+      Ok((MenuId::EMPTY, separator))
+    },
+    NativeMenuItemType::About(_) => make_menu_item(
+      title,
+      Some(selector("orderFrontStandardAboutPanel:")),
+      key_equivalent,
+      menu_type,
+    ),
     NativeMenuItemType::CloseWindow => make_menu_item(
-      title.unwrap_or("Close Window"),
+      title,
       Some(selector("performClose:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "w",
@@ -59,7 +104,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Quit => make_menu_item(
-      title.unwrap_or("Quit"),
+      title,
       Some(selector("terminate:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "q",
@@ -68,7 +113,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Hide => make_menu_item(
-      title.unwrap_or("Hide"),
+      title,
       Some(selector("hide:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "h",
@@ -77,7 +122,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::HideOthers => make_menu_item(
-      title.unwrap_or("Hide Others"),
+      title,
       Some(selector("hideOtherApplications:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "h",
@@ -86,13 +131,13 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::ShowAll => make_menu_item(
-      title.unwrap_or("Show All"),
+      title,
       Some(selector("unhideAllApplications:")),
       None,
       menu_type,
     ),
     NativeMenuItemType::EnterFullScreen => make_menu_item(
-      title.unwrap_or("Enter Full Screen"),
+      title,
       Some(selector("toggleFullScreen:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "f",
@@ -101,7 +146,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Minimize => make_menu_item(
-      title.unwrap_or("Minimize"),
+      title,
       Some(selector("performMiniaturize:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "m",
@@ -109,14 +154,11 @@ pub fn make_native_menu_item(
       })),
       menu_type,
     ),
-    NativeMenuItemType::Zoom => make_menu_item(
-      title.unwrap_or("Zoom"),
-      Some(selector("performZoom:")),
-      None,
-      menu_type,
-    ),
+    NativeMenuItemType::Zoom => {
+      make_menu_item(title, Some(selector("performZoom:")), None, menu_type)
+    }
     NativeMenuItemType::Copy => make_menu_item(
-      title.unwrap_or("Copy"),
+      title,
       Some(selector("copy:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "c",
@@ -125,7 +167,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Cut => make_menu_item(
-      title.unwrap_or("Cut"),
+      title,
       Some(selector("cut:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "x",
@@ -134,7 +176,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Paste => make_menu_item(
-      title.unwrap_or("Paste"),
+      title,
       Some(selector("paste:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "v",
@@ -143,7 +185,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Undo => make_menu_item(
-      title.unwrap_or("Undo"),
+      title,
       Some(selector("undo:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "z",
@@ -152,7 +194,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::Redo => make_menu_item(
-      title.unwrap_or("Redo"),
+      title,
       Some(selector("redo:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "z",
@@ -161,7 +203,7 @@ pub fn make_native_menu_item(
       menu_type,
     ),
     NativeMenuItemType::SelectAll => make_menu_item(
-      title.unwrap_or("Select All"),
+      title,
       Some(selector("selectAll:")),
       Some(key_equivalent.unwrap_or(key::KeyEquivalent {
         key: "a",
@@ -169,15 +211,220 @@ pub fn make_native_menu_item(
       })),
       menu_type,
     ),
+    NativeMenuItemType::BringAllToFront => make_menu_item(
+      title,
+      Some(selector("arrangeInFront:")),
+      key_equivalent,
+      menu_type,
+    ),
+    NativeMenuItemType::Preferences => {
+      // Not bound to a native action, keep the id so the click is sent through the event channel
+      return make_menu_item(
+        title,
+        None,
+        Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+          key: ",",
+          masks: Some(NSEventModifierFlags::NSCommandKeyMask),
+        })),
+        menu_type,
+      )
+      .map(|(menu_id, item)| (Some(menu_id), item));
+    }
+    NativeMenuItemType::Help(_) => make_menu_item(
+      title,
+      Some(selector("showHelp:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "?",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::Find => make_find_panel_item(
+      title,
+      FIND_PANEL_ACTION_SHOW_FIND_PANEL,
+      key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "f",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask),
+      }),
+      menu_type,
+    ),
+    NativeMenuItemType::FindNext => make_find_panel_item(
+      title,
+      FIND_PANEL_ACTION_NEXT,
+      key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "g",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask),
+      }),
+      menu_type,
+    ),
+    NativeMenuItemType::FindPrevious => make_find_panel_item(
+      title,
+      FIND_PANEL_ACTION_PREVIOUS,
+      key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "g",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask | NSEventModifierFlags::NSShiftKeyMask),
+      }),
+      menu_type,
+    ),
+    NativeMenuItemType::Delete => {
+      make_menu_item(title, Some(selector("delete:")), key_equivalent, menu_type)
+    }
+    NativeMenuItemType::PasteAndMatchStyle => make_menu_item(
+      title,
+      Some(selector("pasteAsPlainText:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "v",
+        masks: Some(
+          NSEventModifierFlags::NSCommandKeyMask
+            | NSEventModifierFlags::NSAlternateKeyMask
+            | NSEventModifierFlags::NSShiftKeyMask,
+        ),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::ToggleTabBar => make_menu_item(
+      title,
+      Some(selector("toggleTabBar:")),
+      key_equivalent,
+      menu_type,
+    ),
+    NativeMenuItemType::SelectNextTab => make_menu_item(
+      title,
+      Some(selector("selectNextTab:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "\t",
+        masks: Some(NSEventModifierFlags::NSControlKeyMask),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::SelectPreviousTab => make_menu_item(
+      title,
+      Some(selector("selectPreviousTab:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "\t",
+        masks: Some(NSEventModifierFlags::NSControlKeyMask | NSEventModifierFlags::NSShiftKeyMask),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::ToggleSidebar => make_menu_item(
+      title,
+      Some(selector("toggleSidebar:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "s",
+        masks: Some(
+          NSEventModifierFlags::NSControlKeyMask | NSEventModifierFlags::NSCommandKeyMask,
+        ),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::PageSetup => make_menu_item(
+      title,
+      Some(selector("runPageLayout:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "p",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask | NSEventModifierFlags::NSShiftKeyMask),
+      })),
+      menu_type,
+    ),
+    NativeMenuItemType::Print => make_menu_item(
+      title,
+      Some(selector("print:")),
+      Some(key_equivalent.unwrap_or(key::KeyEquivalent {
+        key: "p",
+        masks: Some(NSEventModifierFlags::NSCommandKeyMask),
+      })),
+      menu_type,
+    ),
     NativeMenuItemType::Services => unsafe {
-      let (_, item) = make_menu_item("Services", None, key_equivalent, menu_type);
-      let app_class = class!(NSApplication);
-      let app: id = msg_send![app_class, sharedApplication];
-      let services: id = msg_send![app, servicesMenu];
+      let (_, item) = make_menu_item(title, None, key_equivalent, menu_type)?;
+      let services = NSMenu::new(nil).autorelease();
+      set_menu_role(services, MenuRole::Services);
       let _: () = msg_send![&*item, setSubmenu: services];
-      (None, item)
+      Ok((MenuId::EMPTY, item))
     },
-  };
+  }?;
+
+  Ok((None, menu_item))
+}
 
-  menu_item
+#[cfg(target_os = "macos")]
+fn make_find_panel_item(
+  title: &str,
+  tag: isize,
+  key_equivalent: key::KeyEquivalent,
+  menu_type: MenuType,
+) -> Result<(MenuId, *mut Object), MenuError> {
+  let (menu_id, item) = make_menu_item(
+    title,
+    Some(selector("performFindPanelAction:")),
+    Some(key_equivalent),
+    menu_type,
+  )?;
+  unsafe {
+    let () = msg_send![item, setTag: tag];
+  }
+  Ok((menu_id, item))
+}
+
+/// Type of a native item read back from a menu, None for custom items and unknown actions.
+///
+/// The app name of `About` and `Help` can't be recovered and is left empty.
+#[cfg(target_os = "macos")]
+pub(crate) unsafe fn native_menu_item_type_of(item: id) -> Option<NativeMenuItemType> {
+  let is_separator: BOOL = msg_send![item, isSeparatorItem];
+  if is_separator != NO {
+    return Some(NativeMenuItemType::Separator);
+  }
+  let submenu: id = msg_send![item, submenu];
+  if submenu != nil {
+    let app: id = msg_send![class!(NSApplication), sharedApplication];
+    let services: id = msg_send![app, servicesMenu];
+    return match submenu == services {
+      true => Some(NativeMenuItemType::Services),
+      false => None,
+    };
+  }
+  // Items without an action return a null selector, which `Sel::name` can't read
+  let action: Sel = msg_send![item, action];
+  if action.as_ptr().is_null() {
+    return None;
+  }
+  let item_type = match action.name() {
+    "orderFrontStandardAboutPanel:" => NativeMenuItemType::About(String::new()),
+    "performClose:" => NativeMenuItemType::CloseWindow,
+    "terminate:" => NativeMenuItemType::Quit,
+    "hide:" => NativeMenuItemType::Hide,
+    "hideOtherApplications:" => NativeMenuItemType::HideOthers,
+    "unhideAllApplications:" => NativeMenuItemType::ShowAll,
+    "toggleFullScreen:" => NativeMenuItemType::EnterFullScreen,
+    "performMiniaturize:" => NativeMenuItemType::Minimize,
+    "performZoom:" => NativeMenuItemType::Zoom,
+    "copy:" => NativeMenuItemType::Copy,
+    "cut:" => NativeMenuItemType::Cut,
+    "paste:" => NativeMenuItemType::Paste,
+    "undo:" => NativeMenuItemType::Undo,
+    "redo:" => NativeMenuItemType::Redo,
+    "selectAll:" => NativeMenuItemType::SelectAll,
+    "arrangeInFront:" => NativeMenuItemType::BringAllToFront,
+    "showHelp:" => NativeMenuItemType::Help(String::new()),
+    "delete:" => NativeMenuItemType::Delete,
+    "pasteAsPlainText:" => NativeMenuItemType::PasteAndMatchStyle,
+    "toggleTabBar:" => NativeMenuItemType::ToggleTabBar,
+    "selectNextTab:" => NativeMenuItemType::SelectNextTab,
+    "selectPreviousTab:" => NativeMenuItemType::SelectPreviousTab,
+    "toggleSidebar:" => NativeMenuItemType::ToggleSidebar,
+    "runPageLayout:" => NativeMenuItemType::PageSetup,
+    "print:" => NativeMenuItemType::Print,
+    "performFindPanelAction:" => {
+      let tag: isize = msg_send![item, tag];
+      match tag {
+        FIND_PANEL_ACTION_SHOW_FIND_PANEL => NativeMenuItemType::Find,
+        FIND_PANEL_ACTION_NEXT => NativeMenuItemType::FindNext,
+        FIND_PANEL_ACTION_PREVIOUS => NativeMenuItemType::FindPrevious,
+        _ => return None,
+      }
+    }
+    _ => return None,
+  };
+  Some(item_type)
 }
@@ -0,0 +1,98 @@
+use cocoa::base::id;
+use objc::{msg_send, sel, sel_impl};
+use std::{
+  fmt,
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+static LIVE_HANDLES: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of `Menu`, `ContextMenu` and `MenuItem` handles (clones included) not dropped yet.
+///
+/// Use it to assert handles are not leaked, such as comparing it before and after building
+/// and dropping a menu.
+pub fn live_handles() -> usize {
+  LIVE_HANDLES.load(Ordering::SeqCst)
+}
+
+/// Strong reference to an Objective-C object.
+///
+/// Retained on create and clone, released on drop, so the object outlives every handle
+/// regardless of the autorelease pool.
+pub(crate) struct Retained(id);
+
+impl Retained {
+  /// Take ownership of an object returned by `alloc`/`new`/`copy`, without retaining it again.
+  pub unsafe fn from_owned(object: id) -> Self {
+    LIVE_HANDLES.fetch_add(1, Ordering::SeqCst);
+    Self(object)
+  }
+  /// Retain an object owned by someone else, such as an autoreleased one.
+  pub unsafe fn retain(object: id) -> Self {
+    let object: id = msg_send![object, retain];
+    Self::from_owned(object)
+  }
+  pub fn as_ptr(&self) -> id {
+    self.0
+  }
+}
+
+impl Clone for Retained {
+  fn clone(&self) -> Self {
+    unsafe { Self::retain(self.0) }
+  }
+}
+
+impl Drop for Retained {
+  fn drop(&mut self) {
+    unsafe {
+      let () = msg_send![self.0, release];
+    }
+    LIVE_HANDLES.fetch_sub(1, Ordering::SeqCst);
+  }
+}
+
+impl fmt::Debug for Retained {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("Retained").field(&self.0).finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use objc::class;
+
+  fn retain_count(object: id) -> usize {
+    unsafe { msg_send![object, retainCount] }
+  }
+
+  // The count is global, so everything creating handles runs in a single test
+  #[test]
+  fn handles_are_counted_and_released() {
+    let baseline = live_handles();
+    unsafe {
+      let object: id = msg_send![class!(NSObject), new];
+      let owned = Retained::from_owned(object);
+      assert_eq!(live_handles(), baseline + 1);
+      assert_eq!(retain_count(object), 1);
+
+      let clone = owned.clone();
+      let retained = Retained::retain(object);
+      assert_eq!(live_handles(), baseline + 3);
+      assert_eq!(retain_count(object), 3);
+
+      drop(clone);
+      drop(retained);
+      assert_eq!(live_handles(), baseline + 1);
+      assert_eq!(retain_count(object), 1);
+
+      // Keep the object alive past the last handle to check it was released
+      let () = msg_send![object, retain];
+      drop(owned);
+      assert_eq!(live_handles(), baseline);
+      assert_eq!(retain_count(object), 1);
+      let () = msg_send![object, release];
+    }
+  }
+}
@@ -0,0 +1,408 @@
+use crate::platform_impl::{
+  key::{KeyEquivalent, NSEventModifierFlags, OwnedKeyEquivalent, MODIFIER_NAMES},
+  menu::MenuId,
+};
+#[cfg(target_os = "macos")]
+use crate::{menu::Menu, menu_index::MenuIndex};
+use std::{
+  collections::{BTreeMap, HashMap},
+  error::Error,
+  fmt, fs, io,
+  path::Path,
+};
+
+/// Error returned when persisted shortcuts can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutParseError {
+  /// 1-based line of the error.
+  pub line: usize,
+  pub message: String,
+}
+
+impl fmt::Display for ShortcutParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.message)
+  }
+}
+
+impl Error for ShortcutParseError {}
+
+/// Several items bound to the same shortcut, see `ShortcutMap::conflicts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutConflict {
+  pub key_equivalent: OwnedKeyEquivalent,
+  /// Sorted
+  pub menu_ids: Vec<MenuId>,
+}
+
+/// Separates the titles of a key made by `ShortcutMap::from_menu`, such as `File > Save`.
+pub const KEY_PATH_SEPARATOR: &str = " > ";
+
+/// Key equivalents of custom items, with user overrides on top of the defaults.
+///
+/// Menu ids are hashes that can change between versions of the app, so overrides are stored
+/// under a stable key of the item instead: its title path or a key given to `set_default`.
+///
+/// Overrides are persisted as one `<item key> = <shortcut>` line per item, such as
+/// `File > Save As… = command+shift+s`, or `File > Save As… = none` to unbind the item.
+/// Lines starting with `#` are comments.
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutMap {
+  defaults: HashMap<MenuId, Option<OwnedKeyEquivalent>>,
+  keys: HashMap<MenuId, String>,
+  // None unbinds the item. Overrides of unknown items are kept, to be saved again
+  overrides: HashMap<String, Option<OwnedKeyEquivalent>>,
+}
+
+impl ShortcutMap {
+  pub fn new() -> Self {
+    Self::default()
+  }
+  /// Use the current key equivalents of the custom items of the menu and its submenus as defaults,
+  /// keyed by their title path.
+  #[cfg(target_os = "macos")]
+  pub fn from_menu(menu: &Menu) -> Self {
+    let mut map = Self::new();
+    for entry in MenuIndex::new(menu).entries() {
+      if !entry.menu_id.is_empty() {
+        map
+          .defaults
+          .insert(entry.menu_id, entry.key_equivalent.clone());
+        map
+          .keys
+          .insert(entry.menu_id, entry.path.join(KEY_PATH_SEPARATOR));
+      }
+    }
+    map
+  }
+  /// Declare the default key equivalent of an item, such as the one of its `MenuItemAttributes`.
+  ///
+  /// `key` identifies the item in the persisted overrides, such as its title path or an id of
+  /// your own. It must not contain ` = ` or line breaks.
+  pub fn set_default(&mut self, menu_id: MenuId, key: &str, key_equivalent: Option<KeyEquivalent>) {
+    self
+      .defaults
+      .insert(menu_id, key_equivalent.map(KeyEquivalent::into_owned));
+    self.keys.insert(menu_id, key.to_string());
+  }
+  /// Key the overrides of an item are persisted under.
+  pub fn key(&self, menu_id: MenuId) -> Option<&str> {
+    self.keys.get(&menu_id).map(String::as_str)
+  }
+  /// Rebind an item, None to unbind it. Returns false for items without a default.
+  pub fn set_override(&mut self, menu_id: MenuId, key_equivalent: Option<KeyEquivalent>) -> bool {
+    match self.keys.get(&menu_id) {
+      Some(key) => {
+        self
+          .overrides
+          .insert(key.clone(), key_equivalent.map(KeyEquivalent::into_owned));
+        true
+      }
+      None => false,
+    }
+  }
+  /// Go back to the default key equivalent of an item.
+  pub fn clear_override(&mut self, menu_id: MenuId) {
+    if let Some(key) = self.keys.get(&menu_id) {
+      self.overrides.remove(key);
+    }
+  }
+  pub fn clear_overrides(&mut self) {
+    self.overrides.clear();
+  }
+  /// Effective key equivalent of an item.
+  pub fn get(&self, menu_id: MenuId) -> Option<&OwnedKeyEquivalent> {
+    let key = self.keys.get(&menu_id);
+    match key.and_then(|key| self.overrides.get(key)) {
+      Some(key_equivalent) => key_equivalent.as_ref(),
+      None => self.defaults.get(&menu_id)?.as_ref(),
+    }
+  }
+  /// Effective key equivalent of every bound item, sorted by id.
+  pub fn bindings(&self) -> BTreeMap<MenuId, OwnedKeyEquivalent> {
+    self
+      .defaults
+      .keys()
+      .filter_map(|menu_id| Some((*menu_id, self.get(*menu_id)?.clone())))
+      .collect()
+  }
+  /// Shortcuts bound to several items, sorted by the smallest id involved.
+  ///
+  /// An uppercase key is the same shortcut as the lowercase key with shift, like AppKit does.
+  pub fn conflicts(&self) -> Vec<ShortcutConflict> {
+    let mut items: HashMap<(String, NSEventModifierFlags), Vec<MenuId>> = HashMap::new();
+    for (menu_id, key_equivalent) in self.bindings() {
+      items
+        .entry(normalize(&key_equivalent))
+        .or_default()
+        .push(menu_id);
+    }
+    let mut conflicts: Vec<ShortcutConflict> = items
+      .into_iter()
+      .filter(|(_, menu_ids)| menu_ids.len() > 1)
+      .map(|((key, masks), menu_ids)| ShortcutConflict {
+        key_equivalent: OwnedKeyEquivalent { key, masks },
+        menu_ids,
+      })
+      .collect();
+    conflicts.sort_by_key(|conflict| conflict.menu_ids[0]);
+    conflicts
+  }
+  /// Set the effective key equivalents on the custom items of the menu and its submenus.
+  /// Items missing from the map are left untouched.
+  #[cfg(target_os = "macos")]
+  pub fn apply(&self, menu: &Menu) {
+    for item in menu.iter() {
+      let menu_id = item.id();
+      if self.defaults.contains_key(&menu_id) {
+        item.set_key_equivalent(self.get(menu_id).map(OwnedKeyEquivalent::as_key_equivalent));
+      }
+    }
+  }
+  /// Add the overrides persisted in `text`, replacing the existing overrides of the same items.
+  pub fn load_overrides(&mut self, text: &str) -> Result<(), ShortcutParseError> {
+    for (index, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let error = |message: String| ShortcutParseError {
+        line: index + 1,
+        message,
+      };
+      // The last separator, titles can contain `=` and shortcuts can't contain spaces
+      let (key, shortcut) = line
+        .rsplit_once(" = ")
+        .ok_or_else(|| error("expected `<item key> = <shortcut>`".to_string()))?;
+      let key_equivalent = parse_shortcut(shortcut.trim()).map_err(error)?;
+      self
+        .overrides
+        .insert(key.trim().to_string(), key_equivalent);
+    }
+    Ok(())
+  }
+  /// Persist the overrides, sorted by key. See `load_overrides`.
+  pub fn export_overrides(&self) -> String {
+    let overrides: BTreeMap<&String, &Option<OwnedKeyEquivalent>> = self.overrides.iter().collect();
+    overrides
+      .into_iter()
+      .map(|(key, key_equivalent)| {
+        let shortcut = match key_equivalent {
+          Some(key_equivalent) => format_shortcut(key_equivalent),
+          None => "none".to_string(),
+        };
+        format!("{} = {}\n", key, shortcut)
+      })
+      .collect()
+  }
+  /// Add the overrides persisted in a file, see `load_overrides`.
+  pub fn load_overrides_from_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    self
+      .load_overrides(&text)
+      .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+  }
+  pub fn save_overrides_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, self.export_overrides())
+  }
+}
+
+fn normalize(key_equivalent: &OwnedKeyEquivalent) -> (String, NSEventModifierFlags) {
+  let key = key_equivalent.key.to_lowercase();
+  let mut masks = key_equivalent.masks;
+  if key != key_equivalent.key {
+    masks |= NSEventModifierFlags::NSShiftKeyMask;
+  }
+  (key, masks)
+}
+
+// Whitespace keys are written by name, lines are trimmed when loaded
+const KEY_NAMES: [(&str, &str); 3] = [("space", " "), ("tab", "\t"), ("return", "\r")];
+
+fn format_shortcut(key_equivalent: &OwnedKeyEquivalent) -> String {
+  let key = KEY_NAMES
+    .iter()
+    .find(|(_, key)| *key == key_equivalent.key)
+    .map_or(key_equivalent.key.as_str(), |(name, _)| *name);
+  MODIFIER_NAMES
+    .iter()
+    .filter(|(_, mask)| key_equivalent.masks.contains(*mask))
+    .map(|(name, _)| *name)
+    .chain(std::iter::once(key))
+    .collect::<Vec<_>>()
+    .join("+")
+}
+
+fn parse_shortcut(shortcut: &str) -> Result<Option<OwnedKeyEquivalent>, String> {
+  if shortcut == "none" {
+    return Ok(None);
+  }
+  let missing_key = || format!("missing key in `{}`", shortcut);
+  let (modifiers, key) = match shortcut.strip_suffix('+') {
+    // The key itself is `+`
+    Some(rest) => match rest.strip_suffix('+') {
+      Some(modifiers) => (Some(modifiers), "+"),
+      None if rest.is_empty() => (None, "+"),
+      None => return Err(missing_key()),
+    },
+    None => match shortcut.rsplit_once('+') {
+      Some((modifiers, key)) => (Some(modifiers), key),
+      None => (None, shortcut),
+    },
+  };
+  if key.is_empty() {
+    return Err(missing_key());
+  }
+  let mut masks = NSEventModifierFlags::empty();
+  for name in modifiers
+    .into_iter()
+    .flat_map(|modifiers| modifiers.split('+'))
+  {
+    let (_, mask) = MODIFIER_NAMES
+      .iter()
+      .find(|(known, _)| *known == name)
+      .ok_or_else(|| format!("unknown modifier `{}`", name))?;
+    masks |= *mask;
+  }
+  let key = KEY_NAMES
+    .iter()
+    .find(|(name, _)| *name == key)
+    .map_or(key, |(_, key)| *key);
+  Ok(Some(OwnedKeyEquivalent {
+    key: key.to_string(),
+    masks,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn shortcut(key: &str, masks: NSEventModifierFlags) -> Option<KeyEquivalent<'_>> {
+    Some(KeyEquivalent {
+      key,
+      masks: Some(masks),
+    })
+  }
+
+  #[test]
+  fn shortcuts_round_trip() {
+    let command = NSEventModifierFlags::NSCommandKeyMask;
+    let shift = NSEventModifierFlags::NSShiftKeyMask;
+    let option = NSEventModifierFlags::NSAlternateKeyMask;
+    let cases = [
+      ("s", command),
+      ("S", command | shift),
+      ("+", command),
+      ("+", NSEventModifierFlags::empty()),
+      ("=", command | option),
+      ("\u{f704}", NSEventModifierFlags::NSFunctionKeyMask),
+      ("a", NSEventModifierFlags::empty()),
+      (" ", command),
+      ("\t", option),
+      ("\r", command | shift),
+    ];
+    for (key, masks) in cases {
+      let key_equivalent = OwnedKeyEquivalent {
+        key: key.to_string(),
+        masks,
+      };
+      let text = format_shortcut(&key_equivalent);
+      assert_eq!(parse_shortcut(&text), Ok(Some(key_equivalent)), "{}", text);
+    }
+    assert_eq!(parse_shortcut("none"), Ok(None));
+    assert_eq!(
+      parse_shortcut("shift+command+s").unwrap(),
+      parse_shortcut("command+shift+s").unwrap()
+    );
+    assert!(parse_shortcut("command+").is_err());
+    assert!(parse_shortcut("super+s").is_err());
+    assert!(parse_shortcut("").is_err());
+
+    let mut map = ShortcutMap::new();
+    map.set_default(MenuId(1), "Play", None);
+    assert!(map.set_override(MenuId(1), shortcut(" ", command)));
+    let text = map.export_overrides();
+    assert_eq!(text, "Play = command+space\n");
+    let mut loaded = ShortcutMap::new();
+    loaded.set_default(MenuId(1), "Play", None);
+    loaded.load_overrides(&text).unwrap();
+    assert_eq!(
+      loaded.get(MenuId(1)),
+      Some(&OwnedKeyEquivalent {
+        key: " ".to_string(),
+        masks: command,
+      })
+    );
+  }
+
+  #[test]
+  fn overrides_are_persisted_by_key() {
+    let command = NSEventModifierFlags::NSCommandKeyMask;
+    let mut map = ShortcutMap::new();
+    map.set_default(MenuId(1), "File > Save", shortcut("s", command));
+    map.set_default(MenuId(2), "View > Zoom = 2x", None);
+    assert!(map.set_override(MenuId(1), None));
+    assert!(map.set_override(MenuId(2), shortcut("=", command)));
+    assert!(!map.set_override(MenuId(3), shortcut("q", command)));
+    let text = map.export_overrides();
+    assert_eq!(text, "File > Save = none\nView > Zoom = 2x = command+=\n");
+
+    // The ids changed, the keys didn't
+    let mut loaded = ShortcutMap::new();
+    loaded.set_default(MenuId(10), "File > Save", shortcut("s", command));
+    loaded.set_default(MenuId(20), "View > Zoom = 2x", None);
+    loaded
+      .load_overrides(&format!("# Comment\n\n{}Removed = command+r\n", text))
+      .unwrap();
+    assert_eq!(loaded.get(MenuId(10)), None);
+    assert_eq!(
+      loaded.get(MenuId(20)),
+      Some(&OwnedKeyEquivalent {
+        key: "=".to_string(),
+        masks: command,
+      })
+    );
+    // Overrides of items that are gone are kept
+    assert!(loaded.export_overrides().contains("Removed = command+r\n"));
+
+    loaded.clear_override(MenuId(10));
+    assert_eq!(loaded.get(MenuId(10)).unwrap().key, "s");
+
+    let error = loaded
+      .load_overrides("File > Save = none\nsave")
+      .unwrap_err();
+    assert_eq!(error.line, 2);
+    let error = loaded.load_overrides("File > Save = meta+s").unwrap_err();
+    assert_eq!(error.message, "unknown modifier `meta`");
+  }
+
+  #[test]
+  fn conflicting_overrides() {
+    let command = NSEventModifierFlags::NSCommandKeyMask;
+    let shift = NSEventModifierFlags::NSShiftKeyMask;
+    let mut map = ShortcutMap::new();
+    map.set_default(MenuId(3), "File > Save", shortcut("s", command));
+    map.set_default(MenuId(1), "File > Save As", shortcut("s", command | shift));
+    map.set_default(MenuId(2), "File > Share", None);
+    assert!(map.conflicts().is_empty());
+
+    // Uppercase is the same shortcut as shift
+    map.set_override(MenuId(2), shortcut("S", command));
+    let conflicts = map.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].menu_ids, vec![MenuId(1), MenuId(2)]);
+
+    map.set_override(MenuId(3), shortcut("s", command | shift));
+    assert_eq!(
+      map.conflicts()[0].menu_ids,
+      vec![MenuId(1), MenuId(2), MenuId(3)]
+    );
+
+    // Unbinding and going back to the defaults resolve the conflicts
+    map.set_override(MenuId(1), None);
+    map.clear_override(MenuId(3));
+    assert!(map.conflicts().is_empty());
+    assert_eq!(map.bindings().len(), 2);
+  }
+}
@@ -0,0 +1,170 @@
+use crate::{
+  accessibility::AccessibilityRole,
+  platform_impl::{
+    key::OwnedKeyEquivalent, menu::MenuId, native_menu_item_type::NativeMenuItemType,
+  },
+};
+#[cfg(target_os = "macos")]
+use crate::{
+  error::MenuError,
+  menu::Menu,
+  platform_impl::{
+    menu::MenuType, menu_item::MenuItem, native_menu_item_type::native_menu_item_type_of,
+  },
+};
+#[cfg(target_os = "macos")]
+use objc::{msg_send, sel, sel_impl};
+
+/// Structure and state of a menu at some point, see `Menu::snapshot`.
+///
+/// With the `serde` feature, snapshots can be serialized, such as to save a customized menu
+/// or to compare the menu against a checked-in JSON file.
+///
+/// Not recorded: custom selectors, restored as items sending menu events, the roles of the
+/// app, window, help and services menus, views set with `MenuItem::set_view` and fonts.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MenuSnapshot {
+  pub title: String,
+  pub items: Vec<ItemSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemSnapshot {
+  pub title: String,
+  pub kind: ItemKind,
+  pub key_equivalent: Option<OwnedKeyEquivalent>,
+  pub enabled: bool,
+  pub selected: bool,
+  /// Not recorded for the Services menu, which the system fills.
+  pub submenu: Option<MenuSnapshot>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub accessibility_label: Option<String>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub accessibility_help: Option<String>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub accessibility_role: Option<AccessibilityRole>,
+  /// Images are not recorded, only whether the item has one.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub has_image: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ItemKind {
+  /// Item sending menu events, including submenu items and `NativeMenuItemType::Preferences`,
+  /// with the id `MenuId::new(title)`.
+  ///
+  /// The hash of the title can change between builds, so it is recomputed when restoring
+  /// rather than recorded.
+  Custom,
+  /// Like `Custom`, with an id that isn't derived from the title, such as one set with
+  /// `CustomItemSpec::with_id`. Recorded as is.
+  CustomWithId(MenuId),
+  Native(NativeMenuItemType),
+  /// Item created outside of this crate with an unknown action, skipped by `Menu::from_snapshot`.
+  Unknown,
+}
+
+#[cfg(target_os = "macos")]
+impl Menu {
+  /// Record the items of this menu and its submenus.
+  pub fn snapshot(&self) -> MenuSnapshot {
+    MenuSnapshot {
+      title: self.title(),
+      items: self.items().iter().map(snapshot_item).collect(),
+    }
+  }
+  /// Build a new menu from a snapshot.
+  pub fn from_snapshot(snapshot: &MenuSnapshot) -> Result<Menu, MenuError> {
+    let menu = Menu::try_new()?;
+    menu.set_title(&snapshot.title);
+    for item in &snapshot.items {
+      if let Some(menu_item) = restore_item(item)? {
+        unsafe {
+          let () = msg_send![menu.as_raw(), addItem: menu_item.as_raw()];
+        }
+      }
+    }
+    Ok(menu)
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn snapshot_item(item: &MenuItem) -> ItemSnapshot {
+  let title = item.title();
+  let kind = match item.id() {
+    menu_id if menu_id == MenuId::new(&title) => ItemKind::Custom,
+    menu_id if !menu_id.is_empty() => ItemKind::CustomWithId(menu_id),
+    _ => match unsafe { native_menu_item_type_of(item.as_raw()) } {
+      Some(item_type) => ItemKind::Native(item_type),
+      None => ItemKind::Unknown,
+    },
+  };
+  let submenu = match kind {
+    ItemKind::Native(NativeMenuItemType::Services) => None,
+    _ => item.submenu().map(|submenu| submenu.snapshot()),
+  };
+  ItemSnapshot {
+    title,
+    kind,
+    key_equivalent: item.key_equivalent(),
+    enabled: item.is_enabled(),
+    selected: item.is_selected(),
+    submenu,
+    accessibility_label: item.accessibility_label(),
+    accessibility_help: item.accessibility_help(),
+    accessibility_role: item.accessibility_role(),
+    has_image: item.has_image(),
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn restore_item(snapshot: &ItemSnapshot) -> Result<Option<MenuItem>, MenuError> {
+  let key_equivalent = snapshot
+    .key_equivalent
+    .as_ref()
+    .map(OwnedKeyEquivalent::as_key_equivalent);
+  let item = match &snapshot.kind {
+    ItemKind::Custom | ItemKind::CustomWithId(_) => {
+      let mut item = match &snapshot.submenu {
+        Some(submenu) => Menu::from_snapshot(submenu)?.make_submenu_item(&snapshot.title)?,
+        None => MenuItem::new(
+          &snapshot.title,
+          None,
+          key_equivalent,
+          snapshot.enabled,
+          snapshot.selected,
+          MenuType::MenuBar,
+        )?,
+      };
+      item.set_id(match snapshot.kind {
+        ItemKind::CustomWithId(menu_id) => menu_id,
+        _ => MenuId::new(&snapshot.title),
+      });
+      item
+    }
+    ItemKind::Native(item_type) => {
+      let item = MenuItem::new_native(
+        item_type.clone(),
+        Some(&snapshot.title),
+        key_equivalent,
+        MenuType::MenuBar,
+      )?;
+      item.set_enabled(snapshot.enabled);
+      item
+    }
+    ItemKind::Unknown => return Ok(None),
+  };
+  if let Some(label) = &snapshot.accessibility_label {
+    item.set_accessibility_label(label);
+  }
+  if let Some(help) = &snapshot.accessibility_help {
+    item.set_accessibility_help(help);
+  }
+  if let Some(role) = snapshot.accessibility_role {
+    item.set_accessibility_role(Some(role));
+  }
+  Ok(Some(item))
+}
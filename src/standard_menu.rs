@@ -0,0 +1,322 @@
+#[cfg(target_os = "macos")]
+use crate::{
+  error::MenuError,
+  localization::{localized_app_item_title, localized_menu_title},
+  menu::Menu,
+  platform_impl::{
+    menu::MenuRole,
+    menu_item::MenuItem,
+    menu_item_attributes::MenuItemAttributes,
+    native_menu_item_type::{native_menu_item_type_of, NativeMenuItemType},
+  },
+};
+#[cfg(target_os = "macos")]
+use std::mem::discriminant;
+
+/// Submenus of the standard application menu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StandardMenu {
+  App,
+  File,
+  Edit,
+  /// Find submenu of the Edit menu.
+  Find,
+  View,
+  Window,
+  Help,
+}
+
+/// Decide which optional parts are included in the standard application menu.
+#[derive(Debug, Clone)]
+pub struct StandardMenuOptions {
+  /// Add a Preferences item to the application menu. Default to true
+  pub preferences: bool,
+  /// Add a File menu with Close Window, Page Setup and Print. Default to true
+  pub file_menu: bool,
+  /// Add a View menu with Enter Full Screen. Default to true
+  pub view_menu: bool,
+  /// Add a Help menu with the app help item. Default to true
+  pub help_menu: bool,
+}
+
+impl StandardMenuOptions {
+  pub fn new() -> Self {
+    Self {
+      preferences: true,
+      file_menu: true,
+      view_menu: true,
+      help_menu: true,
+    }
+  }
+  pub fn with_preferences(mut self, preferences: bool) -> Self {
+    self.preferences = preferences;
+    self
+  }
+  pub fn with_file_menu(mut self, file_menu: bool) -> Self {
+    self.file_menu = file_menu;
+    self
+  }
+  pub fn with_view_menu(mut self, view_menu: bool) -> Self {
+    self.view_menu = view_menu;
+    self
+  }
+  pub fn with_help_menu(mut self, help_menu: bool) -> Self {
+    self.help_menu = help_menu;
+    self
+  }
+}
+
+impl Default for StandardMenuOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Menu bar built by `Menu::standard_app_menu`.
+#[cfg(target_os = "macos")]
+#[derive(Debug, Clone)]
+pub struct StandardAppMenu {
+  pub menu_bar: Menu,
+  /// The Preferences item, use its id to match the click event.
+  pub preferences: Option<MenuItem>,
+  submenus: Vec<(StandardMenu, Menu)>,
+}
+
+#[cfg(target_os = "macos")]
+impl StandardAppMenu {
+  /// Get one of the standard submenus to append more items to it.
+  /// Returns None if the submenu was turned off in `StandardMenuOptions`.
+  pub fn submenu(&self, menu: StandardMenu) -> Option<&Menu> {
+    self
+      .submenus
+      .iter()
+      .find(|(standard_menu, _)| *standard_menu == menu)
+      .map(|(_, submenu)| submenu)
+  }
+  /// Find a standard item in the menu bar, including the Find submenu of the Edit menu.
+  /// The app name of `About` and `Help` is ignored.
+  pub fn find_item(&self, item_type: &NativeMenuItemType) -> Option<MenuItem> {
+    self.locate(item_type).map(|(_, _, item)| item)
+  }
+  /// Insert a custom item right after a standard one.
+  ///
+  /// Returns None if the standard item is not in the menu bar.
+  pub fn insert_item_after(
+    &self,
+    item_type: &NativeMenuItemType,
+    attributes: MenuItemAttributes,
+  ) -> Result<Option<MenuItem>, MenuError> {
+    match self.locate(item_type) {
+      Some((menu, index, _)) => menu.insert_item(index + 1, attributes).map(Some),
+      None => Ok(None),
+    }
+  }
+  /// Replace a standard item by a custom one at the same position,
+  /// such as a Quit item asking to save changes first.
+  ///
+  /// Returns None if the standard item is not in the menu bar.
+  pub fn replace_item(
+    &mut self,
+    item_type: &NativeMenuItemType,
+    attributes: MenuItemAttributes,
+  ) -> Result<Option<MenuItem>, MenuError> {
+    let (menu, index, item) = match self.locate(item_type) {
+      Some(found) => found,
+      None => return Ok(None),
+    };
+    let new_item = menu.insert_item(index, attributes)?;
+    menu.remove_item(&item);
+    if *item_type == NativeMenuItemType::Preferences {
+      self.preferences = None;
+    }
+    Ok(Some(new_item))
+  }
+  /// Remove a standard item, returns false if it is not in the menu bar.
+  pub fn remove_item(&mut self, item_type: &NativeMenuItemType) -> bool {
+    match self.locate(item_type) {
+      Some((menu, _, item)) => {
+        menu.remove_item(&item);
+        if *item_type == NativeMenuItemType::Preferences {
+          self.preferences = None;
+        }
+        true
+      }
+      None => false,
+    }
+  }
+
+  // Menu holding the item, and its position in it
+  fn locate(&self, item_type: &NativeMenuItemType) -> Option<(Menu, usize, MenuItem)> {
+    // Preferences is a custom item, native_menu_item_type_of can't tell it apart
+    let is_match = |item: &MenuItem| match (item_type, &self.preferences) {
+      (NativeMenuItemType::Preferences, Some(preferences)) => unsafe {
+        item.as_raw() == preferences.as_raw()
+      },
+      (NativeMenuItemType::Preferences, None) => false,
+      _ => unsafe { native_menu_item_type_of(item.as_raw()) }
+        .is_some_and(|found| discriminant(&found) == discriminant(item_type)),
+    };
+    self
+      .submenus
+      .iter()
+      .find_map(|(_, submenu)| locate_in(submenu, &is_match))
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn locate_in<F>(menu: &Menu, is_match: &F) -> Option<(Menu, usize, MenuItem)>
+where
+  F: Fn(&MenuItem) -> bool,
+{
+  for (index, item) in menu.items().into_iter().enumerate() {
+    if is_match(&item) {
+      return Some((menu.clone(), index, item));
+    }
+    // Only search submenus built by this crate, not the Services menu
+    let is_native = unsafe { native_menu_item_type_of(item.as_raw()) }.is_some();
+    if let Some(submenu) = item.submenu().filter(|_| !is_native) {
+      if let Some(found) = locate_in(&submenu, is_match) {
+        return Some(found);
+      }
+    }
+  }
+  None
+}
+
+#[cfg(target_os = "macos")]
+impl Menu {
+  /// Build the App/File/Edit/View/Window/Help menu bar described in the macOS Human Interface Guidelines.
+  ///
+  /// Pass the result's `menu_bar` to `set_menu`.
+  pub fn standard_app_menu(
+    app_name: &str,
+    options: StandardMenuOptions,
+  ) -> Result<StandardAppMenu, MenuError> {
+    let menu_bar = Menu::try_new()?;
+    let mut submenus = Vec::new();
+
+    /* application menu */
+    let app_menu = Menu::try_new()?;
+    app_menu.add_native_item(NativeMenuItemType::About(app_name.to_string()), None, None)?;
+    app_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    let preferences = match options.preferences {
+      true => {
+        let item = app_menu.add_native_item(NativeMenuItemType::Preferences, None, None)?;
+        app_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+        Some(item)
+      }
+      false => None,
+    };
+    app_menu.add_native_item(NativeMenuItemType::Services, None, None)?;
+    app_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    app_menu.add_native_item(
+      NativeMenuItemType::Hide,
+      Some(&localized_app_item_title(
+        &NativeMenuItemType::Hide,
+        app_name,
+      )),
+      None,
+    )?;
+    app_menu.add_native_item(NativeMenuItemType::HideOthers, None, None)?;
+    app_menu.add_native_item(NativeMenuItemType::ShowAll, None, None)?;
+    app_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    app_menu.add_native_item(
+      NativeMenuItemType::Quit,
+      Some(&localized_app_item_title(
+        &NativeMenuItemType::Quit,
+        app_name,
+      )),
+      None,
+    )?;
+    // The title of the application menu is always replaced by the app name
+    menu_bar.add_submenu(
+      &app_menu,
+      &localized_menu_title(StandardMenu::App, app_name),
+    )?;
+    app_menu.set_role(MenuRole::App);
+    submenus.push((StandardMenu::App, app_menu));
+
+    /* file menu */
+    if options.file_menu {
+      let file_menu = Menu::try_new()?;
+      file_menu.add_native_item(NativeMenuItemType::CloseWindow, None, None)?;
+      file_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+      file_menu.add_native_item(NativeMenuItemType::PageSetup, None, None)?;
+      file_menu.add_native_item(NativeMenuItemType::Print, None, None)?;
+      menu_bar.add_submenu(
+        &file_menu,
+        &localized_menu_title(StandardMenu::File, app_name),
+      )?;
+      submenus.push((StandardMenu::File, file_menu));
+    }
+
+    /* edit menu */
+    let edit_menu = Menu::try_new()?;
+    edit_menu.add_native_item(NativeMenuItemType::Undo, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Redo, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Cut, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Copy, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Paste, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::PasteAndMatchStyle, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Delete, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::SelectAll, None, None)?;
+    edit_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    let find_menu = Menu::try_new()?;
+    find_menu.add_native_item(NativeMenuItemType::Find, None, None)?;
+    find_menu.add_native_item(NativeMenuItemType::FindNext, None, None)?;
+    find_menu.add_native_item(NativeMenuItemType::FindPrevious, None, None)?;
+    edit_menu.add_submenu(
+      &find_menu,
+      &localized_menu_title(StandardMenu::Find, app_name),
+    )?;
+    menu_bar.add_submenu(
+      &edit_menu,
+      &localized_menu_title(StandardMenu::Edit, app_name),
+    )?;
+    submenus.push((StandardMenu::Edit, edit_menu));
+    submenus.push((StandardMenu::Find, find_menu));
+
+    /* view menu */
+    if options.view_menu {
+      let view_menu = Menu::try_new()?;
+      view_menu.add_native_item(NativeMenuItemType::EnterFullScreen, None, None)?;
+      menu_bar.add_submenu(
+        &view_menu,
+        &localized_menu_title(StandardMenu::View, app_name),
+      )?;
+      submenus.push((StandardMenu::View, view_menu));
+    }
+
+    /* window menu */
+    let window_menu = Menu::try_new()?;
+    window_menu.add_native_item(NativeMenuItemType::Minimize, None, None)?;
+    window_menu.add_native_item(NativeMenuItemType::Zoom, None, None)?;
+    window_menu.add_native_item(NativeMenuItemType::Separator, None, None)?;
+    window_menu.add_native_item(NativeMenuItemType::BringAllToFront, None, None)?;
+    menu_bar.add_submenu(
+      &window_menu,
+      &localized_menu_title(StandardMenu::Window, app_name),
+    )?;
+    window_menu.set_role(MenuRole::Window);
+    submenus.push((StandardMenu::Window, window_menu));
+
+    /* help menu */
+    if options.help_menu {
+      let help_menu = Menu::try_new()?;
+      help_menu.add_native_item(NativeMenuItemType::Help(app_name.to_string()), None, None)?;
+      menu_bar.add_submenu(
+        &help_menu,
+        &localized_menu_title(StandardMenu::Help, app_name),
+      )?;
+      help_menu.set_role(MenuRole::Help);
+      submenus.push((StandardMenu::Help, help_menu));
+    }
+
+    Ok(StandardAppMenu {
+      menu_bar,
+      preferences,
+      submenus,
+    })
+  }
+}
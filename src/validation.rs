@@ -0,0 +1,108 @@
+use crate::platform_impl::menu::MenuId;
+use once_cell::sync::OnceCell;
+use std::sync::{Arc, PoisonError, RwLock};
+
+type ValidationHandler = Arc<dyn Fn(MenuId) -> ItemState + Send + Sync>;
+
+/// State of a custom menu item, returned by the validation handler right before the item
+/// is displayed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemState {
+  /// Default to true
+  pub enabled: bool,
+  /// Default to false
+  pub checked: bool,
+  /// Replace the current title when set. Default to None
+  pub title: Option<String>,
+}
+
+impl ItemState {
+  pub fn new(enabled: bool) -> Self {
+    Self {
+      enabled,
+      ..Default::default()
+    }
+  }
+  pub fn with_checked(mut self, checked: bool) -> Self {
+    self.checked = checked;
+    self
+  }
+  pub fn with_title(mut self, title: &str) -> Self {
+    self.title = Some(title.to_string());
+    self
+  }
+}
+
+impl Default for ItemState {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      checked: false,
+      title: None,
+    }
+  }
+}
+
+fn get_validation_handler() -> &'static RwLock<Option<ValidationHandler>> {
+  static HANDLER: OnceCell<RwLock<Option<ValidationHandler>>> = OnceCell::new();
+  HANDLER.get_or_init(|| RwLock::new(None))
+}
+
+/// Set the handler deciding the state of custom menu items.
+///
+/// The handler is only consulted for menus with auto validation turned on,
+/// see `Menu::set_auto_validation`.
+pub fn set_validation_handler<F>(handler: F)
+where
+  F: Fn(MenuId) -> ItemState + Send + Sync + 'static,
+{
+  *get_validation_handler()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(handler));
+}
+
+/// Remove the validation handler. Items keep the state applied by the last validation.
+pub fn clear_validation_handler() {
+  *get_validation_handler()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn validate(menu_id: MenuId) -> Option<ItemState> {
+  // Clone the handler out so it can replace itself without deadlocking.
+  // A poisoned lock still holds a valid handler, keep validating with it.
+  let handler = get_validation_handler()
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .clone()?;
+  Some(handler(menu_id))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::panic::{catch_unwind, AssertUnwindSafe};
+
+  // The handler is global, so everything runs in a single test
+  #[test]
+  fn validate_survives_poisoned_lock() {
+    set_validation_handler(|menu_id| ItemState::new(menu_id.0 % 2 == 0));
+    assert_eq!(validate(MenuId(2)), Some(ItemState::new(true)));
+
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+      let _guard = get_validation_handler().write().unwrap();
+      panic!("poison the lock");
+    }));
+    assert!(get_validation_handler().is_poisoned());
+    assert_eq!(validate(MenuId(3)), Some(ItemState::new(false)));
+
+    set_validation_handler(|_| ItemState::default().with_checked(true));
+    assert_eq!(
+      validate(MenuId(3)),
+      Some(ItemState::default().with_checked(true))
+    );
+    clear_validation_handler();
+    assert_eq!(validate(MenuId(3)), None);
+  }
+}
@@ -0,0 +1,549 @@
+use cocoa::{
+  appkit::{NSApplication, NSEventModifierFlags},
+  base::{id, nil, BOOL, NO},
+  foundation::NSString,
+};
+use objc::{class, msg_send, runtime::Sel, sel, sel_impl};
+use std::thread;
+use winit_menu_macos::{
+  accessibility::AccessibilityRole,
+  error::{GlobalShortcutError, MenuError},
+  event::Event,
+  event_channel::subscribe,
+  global_shortcut,
+  menu::{ContextMenu, Menu},
+  menu_binding::MenuBinding,
+  menu_font::FontSpec,
+  menu_handle::process_commands,
+  menu_item_view::MenuItemView,
+  menu_spec::{CustomItemSpec, ItemSpec, MenuSpec},
+  platform_impl::{
+    key::KeyEquivalent,
+    menu::{MenuId, MenuTrigger, MenuType},
+    menu_item::MenuItem,
+    menu_item_attributes::MenuItemAttributes,
+    native_menu_item_type::NativeMenuItemType,
+    retained::live_handles,
+  },
+  snapshot::ItemKind,
+};
+
+pub fn run() {
+  let tests: &[(&str, fn())] = &[
+    ("handles_are_released", handles_are_released),
+    ("off_main_thread_is_an_error", off_main_thread_is_an_error),
+    ("handles_insert_items", handles_insert_items),
+    ("activation_sends_menu_events", activation_sends_menu_events),
+    ("tree_inspection", tree_inspection),
+    ("snapshot_round_trip", snapshot_round_trip),
+    ("reconcile_keeps_services", reconcile_keeps_services),
+    ("view_items", view_items),
+    ("fonts_and_layout", fonts_and_layout),
+    ("bindings_update_items", bindings_update_items),
+    (
+      "global_shortcuts_are_replaced",
+      global_shortcuts_are_replaced,
+    ),
+  ];
+  for (name, test) in tests {
+    print!("test {} ... ", name);
+    test();
+    println!("ok");
+  }
+  println!("\ntest result: ok. {} passed", tests.len());
+}
+
+fn handles_are_released() {
+  let baseline = live_handles();
+  {
+    let menu_bar = Menu::new();
+    let submenu = Menu::new();
+    let item = submenu.add_item(MenuItemAttributes::new("Item")).unwrap();
+    submenu
+      .add_native_item(NativeMenuItemType::Copy, None, None)
+      .unwrap();
+    menu_bar.add_submenu(&submenu, "Submenu").unwrap();
+    let _clone = item.clone();
+    let _items: Vec<_> = menu_bar.iter().collect();
+    let context_menu = ContextMenu::new();
+    context_menu
+      .add_item(MenuItemAttributes::new("Context item"))
+      .unwrap();
+    assert!(live_handles() > baseline);
+  }
+  assert_eq!(live_handles(), baseline);
+}
+
+fn off_main_thread_is_an_error() {
+  thread::spawn(|| {
+    assert_eq!(Menu::try_new().err(), Some(MenuError::NotMainThread));
+    assert_eq!(ContextMenu::try_new().err(), Some(MenuError::NotMainThread));
+    assert_eq!(
+      Menu::standard_app_menu("Demo", Default::default()).err(),
+      Some(MenuError::NotMainThread)
+    );
+    assert_eq!(process_commands(), Err(MenuError::NotMainThread));
+  })
+  .join()
+  .unwrap();
+  assert!(Menu::try_new().is_ok());
+  assert_eq!(process_commands(), Ok(()));
+}
+
+fn handles_insert_items() {
+  let menu = Menu::new();
+  let handle = menu.handle();
+  let item = thread::spawn(move || {
+    let item = handle.insert_item(0, MenuItemAttributes::new("Pending"));
+    item.set_title("Inserted");
+    item.set_selected(true);
+    item
+  })
+  .join()
+  .unwrap();
+  process_commands().unwrap();
+  let inserted = menu.iter().next().unwrap();
+  assert_eq!(inserted.title(), "Inserted");
+  assert!(inserted.is_selected());
+  drop(item);
+  process_commands().unwrap();
+  assert_eq!(menu.iter().count(), 1);
+}
+
+// Activating custom items doesn't need a running application
+fn activation_sends_menu_events() {
+  let events = subscribe();
+  let menu_bar = Menu::new();
+  let file_menu = Menu::new();
+  let export = file_menu
+    .add_item(MenuItemAttributes::new("Export"))
+    .unwrap();
+  let disabled = file_menu
+    .add_item(MenuItemAttributes::new("Disabled").with_enabled(false))
+    .unwrap();
+  let checked = file_menu
+    .add_item(MenuItemAttributes::new("Checked").with_selected(true))
+    .unwrap();
+  menu_bar.add_submenu(&file_menu, "File").unwrap();
+
+  assert!(menu_bar.perform_action_by_id(export.id()));
+  match events.try_recv().unwrap() {
+    Event::MenuEvent {
+      menu_id,
+      menu_type,
+      trigger,
+      checked,
+      path,
+      ..
+    } => {
+      assert_eq!(menu_id, export.id());
+      assert_eq!(menu_type, MenuType::MenuBar);
+      assert_eq!(trigger, MenuTrigger::Programmatic);
+      assert!(!checked);
+      assert_eq!(path, vec!["File".to_string()]);
+    }
+    event => panic!("unexpected event {:?}", event),
+  }
+
+  assert!(checked.activate());
+  assert!(matches!(
+    events.try_recv().unwrap(),
+    Event::MenuEvent { checked: true, .. }
+  ));
+
+  assert!(!disabled.activate());
+  assert!(!menu_bar.perform_action_by_id(disabled.id()));
+  assert!(!menu_bar.perform_action_by_id(MenuId::new("Missing")));
+  assert!(events.try_recv().is_err());
+
+  let context_menu = ContextMenu::new();
+  context_menu
+    .add_item(MenuItemAttributes::new("Context item"))
+    .unwrap();
+  assert!(context_menu.perform_action_by_id(MenuId::new("Context item")));
+  assert!(matches!(
+    events.try_recv().unwrap(),
+    Event::MenuEvent {
+      menu_type: MenuType::ContextMenu,
+      ..
+    }
+  ));
+}
+
+fn titles(items: impl IntoIterator<Item = MenuItem>) -> Vec<String> {
+  items.into_iter().map(|item| item.title()).collect()
+}
+
+fn tree_inspection() {
+  // File > Export > PNG, with a second "Export" item without submenu before the submenu
+  let menu_bar = Menu::new();
+  let file_menu = Menu::new();
+  let export_menu = Menu::new();
+  let png = export_menu
+    .add_item(MenuItemAttributes::new("PNG"))
+    .unwrap();
+  export_menu
+    .add_item(MenuItemAttributes::new("PNG").with_enabled(false))
+    .unwrap();
+  file_menu
+    .add_item(MenuItemAttributes::new("Export"))
+    .unwrap();
+  file_menu.add_submenu(&export_menu, "Export").unwrap();
+  file_menu
+    .add_native_item(NativeMenuItemType::Separator, None, None)
+    .unwrap();
+  menu_bar.add_submenu(&file_menu, "File").unwrap();
+  let edit_menu = Menu::new();
+  edit_menu
+    .add_native_item(NativeMenuItemType::Copy, Some("Copy"), None)
+    .unwrap();
+  menu_bar.add_submenu(&edit_menu, "Edit").unwrap();
+
+  assert_eq!(titles(menu_bar.items()), vec!["File", "Edit"]);
+  assert_eq!(
+    titles(menu_bar.iter()),
+    vec!["File", "Export", "Export", "PNG", "PNG", "", "Edit", "Copy"]
+  );
+  assert!(menu_bar.iter().nth(5).unwrap().is_separator());
+
+  // Nested paths, following the submenu of duplicate titles and returning the first match
+  let found = menu_bar
+    .find_by_title_path(&["File", "Export", "PNG"])
+    .unwrap();
+  assert_eq!(found.id(), png.id());
+  assert!(found.is_enabled());
+  assert!(menu_bar
+    .find_by_title_path(&["File", "Export"])
+    .unwrap()
+    .submenu()
+    .is_none());
+  assert_eq!(
+    menu_bar
+      .find_by_title_path(&["Edit", "Copy"])
+      .unwrap()
+      .title(),
+    "Copy"
+  );
+  assert_eq!(menu_bar.find_by_id(png.id()).unwrap().title(), "PNG");
+
+  // Missing paths
+  assert!(menu_bar.find_by_title_path(&[]).is_none());
+  assert!(menu_bar.find_by_title_path(&["View"]).is_none());
+  assert!(menu_bar
+    .find_by_title_path(&["File", "Import", "PNG"])
+    .is_none());
+  assert!(menu_bar
+    .find_by_title_path(&["File", "Export", "JPEG"])
+    .is_none());
+  assert!(menu_bar
+    .find_by_title_path(&["Edit", "Copy", "Copy"])
+    .is_none());
+  assert!(menu_bar.find_by_id(MenuId::new("Missing")).is_none());
+}
+
+fn snapshot_round_trip() {
+  let menu_bar = Menu::new();
+  let edit_menu = Menu::new();
+  let rename = edit_menu
+    .add_item(
+      MenuItemAttributes::new("Rename")
+        .with_selected(true)
+        .with_accessibility_role(AccessibilityRole::CheckBox),
+    )
+    .unwrap();
+  edit_menu
+    .add_native_item(NativeMenuItemType::Copy, Some("Copy"), None)
+    .unwrap();
+  edit_menu
+    .add_native_item(NativeMenuItemType::Separator, None, None)
+    .unwrap();
+  edit_menu
+    .add_native_item(NativeMenuItemType::Find, Some("Find…"), None)
+    .unwrap();
+  menu_bar.add_submenu(&edit_menu, "Edit").unwrap();
+  let window_menu = Menu::new();
+  window_menu
+    .add_native_item(NativeMenuItemType::Minimize, Some("Minimize"), None)
+    .unwrap();
+  menu_bar.add_submenu(&window_menu, "Window").unwrap();
+  let file_menu = Menu::new();
+  file_menu
+    .reconcile(&MenuSpec::new().with_item(ItemSpec::Item(
+      CustomItemSpec::new("Export").with_id(MenuId(42)),
+    )))
+    .unwrap();
+  menu_bar.add_submenu(&file_menu, "File").unwrap();
+
+  let snapshot = menu_bar.snapshot();
+  assert_eq!(
+    snapshot.items[2].submenu.as_ref().unwrap().items[0].kind,
+    ItemKind::CustomWithId(MenuId(42))
+  );
+  let edit_items = &snapshot.items[0].submenu.as_ref().unwrap().items;
+  assert_eq!(
+    edit_items
+      .iter()
+      .map(|item| item.kind.clone())
+      .collect::<Vec<_>>(),
+    vec![
+      ItemKind::Custom,
+      ItemKind::Native(NativeMenuItemType::Copy),
+      ItemKind::Native(NativeMenuItemType::Separator),
+      ItemKind::Native(NativeMenuItemType::Find),
+    ]
+  );
+  assert_eq!(
+    edit_items[0].accessibility_role,
+    Some(AccessibilityRole::CheckBox)
+  );
+  let restored = Menu::from_snapshot(&snapshot).unwrap();
+  assert_eq!(restored.snapshot(), snapshot);
+  rename.set_accessibility_role(None);
+  assert_ne!(
+    rename.accessibility_role(),
+    Some(AccessibilityRole::CheckBox)
+  );
+
+  // Items without an action have a null selector
+  let other = Menu::new();
+  unsafe {
+    use cocoa::{
+      appkit::{NSMenu, NSMenuItem},
+      base::nil,
+      foundation::NSString,
+    };
+    let title = NSString::alloc(nil).init_str("No action");
+    let key = NSString::alloc(nil).init_str("");
+    let item = NSMenuItem::alloc(nil).initWithTitle_action_keyEquivalent_(
+      title,
+      objc::runtime::Sel::from_ptr(std::ptr::null()),
+      key,
+    );
+    other.as_raw().addItem_(item);
+  }
+  assert_eq!(other.snapshot().items[0].kind, ItemKind::Unknown);
+}
+
+fn global_shortcuts_are_replaced() {
+  let shortcut = |key| KeyEquivalent {
+    key,
+    masks: Some(
+      NSEventModifierFlags::NSCommandKeyMask
+        | NSEventModifierFlags::NSAlternateKeyMask
+        | NSEventModifierFlags::NSControlKeyMask,
+    ),
+  };
+  let first = MenuId::new("Global shortcut test 1");
+  let second = MenuId::new("Global shortcut test 2");
+  global_shortcut::register(first, shortcut("\u{f70f}")).unwrap();
+  // Registering the same shortcut again keeps it
+  global_shortcut::register(first, shortcut("\u{f70f}")).unwrap();
+  global_shortcut::register(first, shortcut("\u{f70e}")).unwrap();
+  global_shortcut::register(second, shortcut("\u{f70f}")).unwrap();
+
+  // The hot key of the other item can't be registered, the previous shortcut is kept
+  assert!(matches!(
+    global_shortcut::register(first, shortcut("\u{f70f}")),
+    Err(GlobalShortcutError::Registration(_))
+  ));
+  let registered = global_shortcut::registered();
+  let key_of = |menu_id| {
+    registered
+      .iter()
+      .find(|(id, _)| *id == menu_id)
+      .map(|(_, key_equivalent)| key_equivalent.key.clone())
+  };
+  assert_eq!(key_of(first).as_deref(), Some("\u{f70e}"));
+  assert_eq!(key_of(second).as_deref(), Some("\u{f70f}"));
+
+  assert_eq!(global_shortcut::unregister(first), Ok(true));
+  assert_eq!(global_shortcut::unregister(first), Ok(false));
+  // Its hot key was released
+  global_shortcut::register(second, shortcut("\u{f70e}")).unwrap();
+  assert_eq!(global_shortcut::unregister(second), Ok(true));
+  thread::spawn(move || {
+    assert_eq!(
+      global_shortcut::unregister(second),
+      Err(GlobalShortcutError::NotMainThread)
+    );
+  })
+  .join()
+  .unwrap();
+  assert!(global_shortcut::registered().is_empty());
+}
+
+fn reconcile_keeps_services() {
+  // Services is told apart by `NSApp().servicesMenu`
+  unsafe { NSApplication::sharedApplication(nil) };
+  let spec = MenuSpec::new()
+    .with_item(ItemSpec::Item(CustomItemSpec::new("Settings")))
+    .with_item(ItemSpec::Native(NativeMenuItemType::Separator))
+    .with_item(ItemSpec::Native(NativeMenuItemType::Services))
+    .with_item(ItemSpec::Native(NativeMenuItemType::Separator))
+    .with_item(ItemSpec::Native(NativeMenuItemType::Quit));
+  let menu = Menu::new();
+  menu.reconcile(&spec).unwrap();
+  let raw = |menu: &Menu| -> Vec<usize> {
+    menu
+      .items()
+      .iter()
+      .map(|item| unsafe { item.as_raw() } as usize)
+      .collect()
+  };
+  let before = raw(&menu);
+  assert_eq!(before.len(), 5);
+  menu.reconcile(&spec).unwrap();
+  assert_eq!(raw(&menu), before);
+}
+
+// First subview of the view of the item
+unsafe fn control(item: &MenuItem) -> id {
+  let view: id = msg_send![item.as_raw(), view];
+  let subviews: id = msg_send![view, subviews];
+  msg_send![subviews, objectAtIndex: 0_usize]
+}
+
+unsafe fn string(ns_string: id) -> String {
+  std::ffi::CStr::from_ptr(ns_string.UTF8String())
+    .to_string_lossy()
+    .into_owned()
+}
+
+fn view_items() {
+  let events = subscribe();
+  let menu = Menu::new();
+  let slider_view = |value| MenuItemView::Slider {
+    min: 0.,
+    max: 10.,
+    value,
+  };
+  let volume = menu.add_view_item("Volume", slider_view(5.)).unwrap();
+  for value in [11., f64::NAN] {
+    assert_eq!(
+      menu.add_view_item("Invalid", slider_view(value)).err(),
+      Some(MenuError::InvalidSlider)
+    );
+  }
+  unsafe {
+    let slider = control(&volume);
+    assert_eq!(string(msg_send![slider, accessibilityLabel]), "Volume");
+    // As AppKit does while the slider is dragged
+    let () = msg_send![slider, setDoubleValue: 7_f64];
+    let target: id = msg_send![slider, target];
+    let action: Sel = msg_send![slider, action];
+    let _: id = msg_send![target, performSelector: action withObject: slider];
+    match events.try_recv().unwrap() {
+      Event::ViewValueChanged { menu_id, value } => {
+        assert_eq!(menu_id, volume.id());
+        assert_eq!(value, 7.);
+      }
+      event => panic!("unexpected event {:?}", event),
+    }
+    volume.set_view_value(Some(20.));
+    let value: f64 = msg_send![slider, doubleValue];
+    assert_eq!(value, 10.);
+  }
+
+  let progress = menu
+    .add_view_item("Download", MenuItemView::Progress { value: Some(0.2) })
+    .unwrap();
+  unsafe {
+    let bar = control(&progress);
+    progress.set_view_value(Some(0.5));
+    let value: f64 = msg_send![bar, doubleValue];
+    let indeterminate: BOOL = msg_send![bar, isIndeterminate];
+    assert_eq!((value, indeterminate), (0.5, NO));
+    progress.set_view_value(None);
+    let indeterminate: BOOL = msg_send![bar, isIndeterminate];
+    assert_ne!(indeterminate, NO);
+  }
+
+  let status = menu
+    .add_view_item(
+      "Status",
+      MenuItemView::Label {
+        text: "Idle".to_string(),
+        image: None,
+      },
+    )
+    .unwrap();
+  status.set_view_text("Playing");
+  unsafe {
+    assert_eq!(string(msg_send![control(&status), stringValue]), "Playing");
+  }
+
+  let plain = menu.add_item(MenuItemAttributes::new("Plain")).unwrap();
+  plain.set_view_value(Some(1.)).set_view_text("Ignored");
+  unsafe {
+    let view: id = msg_send![plain.as_raw(), view];
+    assert_eq!(view, nil);
+  }
+  assert_eq!(plain.title(), "Plain");
+}
+
+fn fonts_and_layout() {
+  let menu = Menu::new();
+  menu.set_font(&FontSpec::new().with_family("Menlo"));
+  menu.set_minimum_width(300.);
+  menu.set_shows_state_column(false);
+  unsafe {
+    let menu_font: id = msg_send![class!(NSFont), menuFontOfSize: 0_f64];
+    let default_size: f64 = msg_send![menu_font, pointSize];
+    let font: id = msg_send![menu.as_raw(), font];
+    let size: f64 = msg_send![font, pointSize];
+    assert_eq!(size, default_size);
+    assert!(string(msg_send![font, familyName]).starts_with("Menlo"));
+    let width: f64 = msg_send![menu.as_raw(), minimumWidth];
+    assert_eq!(width, 300.);
+    let shows_state_column: BOOL = msg_send![menu.as_raw(), showsStateColumn];
+    assert_eq!(shows_state_column, NO);
+  }
+
+  let item = menu.add_item(MenuItemAttributes::new("Large")).unwrap();
+  item.set_font(Some(&FontSpec::new().with_size(18.)));
+  // The font outlives the title
+  item.set_title("Larger");
+  unsafe {
+    let attributed_title: id = msg_send![item.as_raw(), attributedTitle];
+    assert_eq!(string(msg_send![attributed_title, string]), "Larger");
+    let attributes: id = msg_send![attributed_title, attributesAtIndex: 0_usize effectiveRange: std::ptr::null_mut::<u8>()];
+    let key = NSString::alloc(nil).init_str("NSFont");
+    let font: id = msg_send![attributes, objectForKey: key];
+    let size: f64 = msg_send![font, pointSize];
+    assert_eq!(size, 18.);
+  }
+  item.set_font(None);
+  item.set_title("Regular");
+  unsafe {
+    let attributed_title: id = msg_send![item.as_raw(), attributedTitle];
+    assert_eq!(attributed_title, nil);
+  }
+  assert_eq!(item.title(), "Regular");
+}
+
+fn bindings_update_items() {
+  let menu = Menu::new();
+  let item = menu.add_item(MenuItemAttributes::new("Sidebar")).unwrap();
+  let show_sidebar = MenuBinding::new(false);
+  show_sidebar.bind_selected(&item, |shown| *shown);
+  show_sidebar.bind_title(&item, |shown| match shown {
+    true => "Hide Sidebar".to_string(),
+    false => "Show Sidebar".to_string(),
+  });
+  process_commands().unwrap();
+  assert!(!item.is_selected());
+  assert_eq!(item.title(), "Show Sidebar");
+
+  show_sidebar.set(true);
+  // Queued until then
+  assert!(!item.is_selected());
+  process_commands().unwrap();
+  assert!(item.is_selected());
+  assert_eq!(item.title(), "Hide Sidebar");
+
+  show_sidebar.unbind(&item);
+  show_sidebar.set(false);
+  process_commands().unwrap();
+  assert!(item.is_selected());
+  assert_eq!(item.title(), "Hide Sidebar");
+}
@@ -0,0 +1,13 @@
+//! Tests driving real AppKit menus. They run one after the other on the main thread.
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(target_os = "macos")]
+fn main() {
+  macos::run();
+}
+
+// AppKit is only available on macOS
+#[cfg(not(target_os = "macos"))]
+fn main() {}